@@ -1,44 +1,504 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{mint_to, MintTo, burn, Burn, Mint, Token, TokenAccount},
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    metadata::{
+        create_metadata_accounts_v3, mpl_token_metadata::types::DataV2,
+        CreateMetadataAccountsV3, Metadata,
+    },
+    token_interface::{
+        self, burn, mint_to, spl_token_2022::instruction::AuthorityType, Burn, FreezeAccount,
+        Mint, MintTo, SetAuthority, ThawAccount, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
+use anchor_lang::solana_program::keccak;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_lang::solana_program::program_option::COption;
 use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
 
 declare_id!("EN2SeC45TuHgrLg33ZhJLsYSX5gxnunrVm5P6Dx5eiRS");
 
+// Registrar uma assinatura rejeitada (trilha forense de tentativas de
+// falsificação) e falhar com `code`. O evento fica nos logs da transação
+// revertida; global_seq é 0 porque a reversão descarta o incremento. Uma
+// falha ao ler o Clock não pode mascarar o motivo da rejeição
+fn signature_rejected(user: &Pubkey, code: ErrorCode) -> Result<()> {
+    emit!(SecurityEvent {
+        event_type: "SIG_REJECT".to_string(),
+        user: *user,
+        reason: code.name(),
+        timestamp: Clock::get().map(|clock| clock.unix_timestamp).unwrap_or_default(),
+        global_seq: 0,
+    });
+    Err(error!(code))
+}
+
 pub fn verify_signature(
     sysvar_instructions: &AccountInfo,
-    _message: &[u8],
-    _signature: &[u8; 64],
-    _public_key: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+    public_key: &Pubkey,
+    user: &Pubkey,
 ) -> Result<()> {
     use anchor_lang::solana_program::ed25519_program;
 
-    let instruction_sysvar = sysvar_instructions::load_current_index_checked(sysvar_instructions)?;
+    let current_index = sysvar_instructions::load_current_index_checked(sysvar_instructions)? as usize;
 
-    // A instrução ED25519 deve estar na posição anterior (index - 1)
-    if instruction_sysvar > 0 {
-        let ed25519_ix_index = (instruction_sysvar - 1) as u8;
-        let current_ix = sysvar_instructions::load_instruction_at_checked(
-            ed25519_ix_index as usize,
-            sysvar_instructions,
-        )?;
+    // Percorrer todas as instruções anteriores à atual procurando uma instrução
+    // ED25519 cujo payload (pubkey, assinatura e mensagem) corresponda ao
+    // esperado, em vez de assumir que ela está em index - 1
+    let mut found_ed25519 = false;
+    for index in 0..current_index {
+        let ix = sysvar_instructions::load_instruction_at_checked(index, sysvar_instructions)?;
+        if ix.program_id != ed25519_program::ID {
+            continue;
+        }
+        found_ed25519 = true;
+        if ed25519_payload_matches(&ix.data, message, signature, public_key) {
+            msg!("ED25519 signature verification passed (ix {})", index);
+            return Ok(());
+        }
+    }
 
-        // Verificar se é uma instrução ED25519 válida
-        require!(
-            current_ix.program_id == ed25519_program::ID,
-            ErrorCode::InvalidSignature
-        );
+    // Distinguir "nenhuma instrução ED25519" de "assinatura não confere"
+    if !found_ed25519 {
+        return signature_rejected(user, ErrorCode::MissingEd25519Instruction);
+    }
+    signature_rejected(user, ErrorCode::InvalidSignature)
+}
 
-        msg!("ED25519 signature verification passed");
-    } else {
-        return err!(ErrorCode::InvalidSignature);
+// Emitir as compute units consumidas desde `cu_start` (feature `profiling`)
+#[cfg(feature = "profiling")]
+fn emit_compute_profile(op: &str, cu_start: u64) {
+    let cu_end = anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
+    emit!(ComputeProfileEvent {
+        op: op.to_string(),
+        consumed: cu_start.saturating_sub(cu_end),
+    });
+}
+
+// Linha compacta key=value da feature `structured-logs`, ex.
+// "op=claim user=.. amount=.. total=.."
+#[cfg(feature = "structured-logs")]
+fn structured_log_line(op: &str, fields: &[(&str, &dyn std::fmt::Display)]) -> String {
+    fields
+        .iter()
+        .fold(format!("op={}", op), |line, (key, value)| format!("{} {}={}", line, key, value))
+}
+
+// Consultar a blacklist sem exigir que ela tenha sido inicializada: um PDA
+// vazio significa "ninguém banido" (a flag por usuário continua valendo).
+// O endereço é validado pelas seeds, então a conta não pode ser omitida
+fn blacklist_contains(blacklist: &AccountInfo, user: &Pubkey) -> Result<bool> {
+    if blacklist.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(*blacklist.owner, crate::ID, ErrorCode::InvalidInput);
+    let data = blacklist.try_borrow_data()?;
+    let blacklist = BlacklistAccount::try_deserialize(&mut &data[..])?;
+    Ok(blacklist.blacklisted_users.contains(user))
+}
+
+// Banimento temporário ainda em vigor? O PDA [b"blacklist_entry", user] pode
+// não existir (nunca banido ou já podado); o endereço é validado pelas seeds
+fn blacklist_entry_active(entry: &AccountInfo, now: i64) -> Result<bool> {
+    if entry.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(*entry.owner, crate::ID, ErrorCode::InvalidInput);
+    let data = entry.try_borrow_data()?;
+    Ok(!BlacklistEntry::try_deserialize(&mut &data[..])?.expired(now))
+}
+
+// Ler a UserClaimAccount principal [b"user_claim", user], que guarda o
+// banimento e a suspensão do usuário para todos os tokens. None se o usuário
+// nunca teve a conta criada; o endereço é validado pelas seeds
+fn load_user_claim(account: &AccountInfo) -> Result<Option<UserClaimAccount>> {
+    if account.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*account.owner, crate::ID, ErrorCode::InvalidInput);
+    let data = account.try_borrow_data()?;
+    Ok(Some(UserClaimAccount::try_deserialize(&mut &data[..])?))
+}
+
+// Permanent delegate (extensão do Token-2022) configurado no mint. None para
+// mints do SPL Token clássico ou sem a extensão
+fn mint_permanent_delegate(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    use anchor_spl::token_interface::spl_token_2022::{
+        extension::{
+            permanent_delegate::PermanentDelegate, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Mint as MintState,
+    };
+
+    if *mint.owner != anchor_spl::token_interface::spl_token_2022::ID {
+        return Ok(None);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    Ok(state
+        .get_extension::<PermanentDelegate>()
+        .ok()
+        .and_then(|extension| Option::<Pubkey>::from(extension.delegate)))
+}
+
+// Validar o tamanho de um batch de blacklist e remover usuários repetidos
+fn dedupe_blacklist_batch(mut users: Vec<Pubkey>) -> Result<Vec<Pubkey>> {
+    require!(!users.is_empty(), ErrorCode::InvalidInput);
+    require!(
+        users.len() <= MAX_BLACKLIST_BATCH_SIZE,
+        ErrorCode::BatchTooLarge
+    );
+    users.sort();
+    users.dedup();
+    Ok(users)
+}
+
+// Atualizar a flag is_blacklisted das UserClaimAccount passadas em
+// remaining_accounts. Cada conta precisa ser a conta principal de um usuário
+// do batch (as contas por mint não guardam banimento).
+// Retorna quantas contas foram alteradas.
+fn sync_blacklist_flags<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    users: &[Pubkey],
+    is_blacklisted: bool,
+) -> Result<u32> {
+    let mut synced: u32 = 0;
+    for account_info in accounts.iter() {
+        require!(account_info.is_writable, ErrorCode::InvalidInput);
+        // try_from valida owner (este programa) e o discriminator da conta
+        let mut user_claim = Account::<UserClaimAccount>::try_from(account_info)?;
+        require!(users.contains(&user_claim.user), ErrorCode::InvalidInput);
+        let main_pda = Pubkey::create_program_address(
+            &[b"user_claim", user_claim.user.as_ref(), &[user_claim.bump]],
+            &crate::ID,
+        ).map_err(|_| error!(ErrorCode::InvalidInput))?;
+        require_keys_eq!(account_info.key(), main_pda, ErrorCode::InvalidInput);
+
+        if user_claim.is_blacklisted != is_blacklisted {
+            user_claim.is_blacklisted = is_blacklisted;
+            if !is_blacklisted {
+                user_claim.appeal_requested = false;
+            }
+            user_claim.exit(&crate::ID)?;
+            synced += 1;
+        }
+    }
+    Ok(synced)
+}
+
+// Verificar a prova merkle de `leaf` contra `root`. Cada nível faz keccak do
+// par ordenado, no formato dos merkle distributors. Na allowlist a folha é
+// keccak(pubkey); nos claims em lote, keccak(mensagem do claim)
+fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    use keccak::hashv;
+
+    if proof.len() > MAX_MERKLE_PROOF_LEN {
+        return false;
+    }
+
+    let mut node = leaf;
+    for sibling in proof.iter() {
+        node = if node <= *sibling {
+            hashv(&[&node, sibling]).0
+        } else {
+            hashv(&[sibling, &node]).0
+        };
+    }
+    node == *root
+}
+
+// Layout dos offsets de cada assinatura na instrução do programa ED25519
+const ED25519_SIGNATURE_OFFSETS_START: usize = 2;
+const ED25519_SIGNATURE_OFFSETS_SIZE: usize = 14;
+
+// Verificar se alguma das assinaturas da instrução ED25519 corresponde à
+// mensagem, assinatura e pubkey esperadas. Só aceita dados contidos na
+// própria instrução ED25519 (instruction_index == u16::MAX)
+fn ed25519_payload_matches(
+    data: &[u8],
+    message: &[u8],
+    signature: &[u8; 64],
+    public_key: &Pubkey,
+) -> bool {
+    let read_u16 = |offset: usize| -> Option<u16> {
+        data.get(offset..offset + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    };
+    let slice = |offset: u16, len: usize| -> Option<&[u8]> {
+        data.get(offset as usize..(offset as usize).checked_add(len)?)
+    };
+
+    let num_signatures = match data.first() {
+        Some(n) => *n as usize,
+        None => return false,
+    };
+
+    (0..num_signatures).any(|i| {
+        let base = ED25519_SIGNATURE_OFFSETS_START + i * ED25519_SIGNATURE_OFFSETS_SIZE;
+        let fields: Option<[u16; 7]> = (|| {
+            Some([
+                read_u16(base)?,
+                read_u16(base + 2)?,
+                read_u16(base + 4)?,
+                read_u16(base + 6)?,
+                read_u16(base + 8)?,
+                read_u16(base + 10)?,
+                read_u16(base + 12)?,
+            ])
+        })();
+        let [sig_offset, sig_ix, pk_offset, pk_ix, msg_offset, msg_size, msg_ix] = match fields {
+            Some(f) => f,
+            None => return false,
+        };
+
+        if sig_ix != u16::MAX || pk_ix != u16::MAX || msg_ix != u16::MAX {
+            return false;
+        }
+
+        slice(sig_offset, 64) == Some(&signature[..])
+            && slice(pk_offset, 32) == Some(public_key.as_ref())
+            && slice(msg_offset, msg_size as usize) == Some(message)
+    })
+}
+
+// Tolerância (em segundos) para o timestamp assinado pelo backend
+pub const SIGNATURE_TOLERANCE_SECONDS: i64 = 300;
+
+// Validar o timestamp assinado. Por padrão só aceita timestamps no passado
+// (timestamp <= now); timestamps futuros só são aceitos com allow_future.
+pub fn validate_signature_timestamp(
+    now: i64,
+    timestamp: i64,
+    allow_future: bool,
+    user: &Pubkey,
+) -> Result<()> {
+    if timestamp > now {
+        if !allow_future {
+            return signature_rejected(user, ErrorCode::SignatureFromFuture);
+        }
+        if timestamp - now > SIGNATURE_TOLERANCE_SECONDS {
+            return signature_rejected(user, ErrorCode::ExpiredSignature);
+        }
+    } else if now - timestamp > SIGNATURE_TOLERANCE_SECONDS {
+        return signature_rejected(user, ErrorCode::ExpiredSignature);
     }
 
     Ok(())
 }
 
+// Tamanho máximo (em bytes UTF-8) da descrição de uma queima
+pub const MAX_DESC_LEN: usize = 256;
+
+// Código de motivo de queima "não especificado" (apenas a descrição livre)
+pub const BURN_REASON_UNSPECIFIED: u8 = 0;
+
+// Tamanho máximo (em bytes UTF-8) do motivo de uma pausa de emergência
+pub const MAX_PAUSE_REASON_LEN: usize = 128;
+
+// Tamanho padrão máximo de um batch_mint_tokens (ajustável pelo admin)
+pub const DEFAULT_MAX_BATCH_SIZE: u16 = 20;
+
+// Delay obrigatório entre solicitar e executar uma ação administrativa
+pub const ADMIN_ACTION_DELAY_SECONDS: i64 = 24 * 60 * 60; // 24 horas
+
+// Limites do Token Metadata (Metaplex) para nome, símbolo e URI
+pub const MAX_METADATA_NAME_LEN: usize = 32;
+pub const MAX_METADATA_SYMBOL_LEN: usize = 10;
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+// Janelas dos limites de claim (deslizantes sobre o histórico do usuário)
+pub const ONE_DAY_SECONDS: i64 = 24 * 60 * 60;
+pub const ONE_HOUR_SECONDS: i64 = 60 * 60;
+
+// Janela do limite semanal. É fixa (weekly_reset_timestamp), pois o histórico
+// de claims só guarda as últimas 24h
+pub const ONE_WEEK_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+// Hash de sessão de um claim sem prova de gameplay (não cria ClaimedSession)
+pub const NO_GAME_SESSION: [u8; 32] = [0u8; 32];
+
+// Tempo mínimo que o registro de uma sessão de gameplay fica on-chain antes
+// que o claimer possa fechá-lo e recuperar o rent (close_claimed_session)
+pub const SESSION_RETENTION_SECONDS: i64 = 30 * ONE_DAY_SECONDS;
+
+// Categorias de recompensa (login diário, conquistas, compras...). A
+// categoria 0 usa os limites gerais; as demais, o limite diário configurado
+pub const MAX_CLAIM_CATEGORIES: usize = 8;
+pub const DEFAULT_CLAIM_CATEGORY: u8 = 0;
+
+// Máximo de claims por usuário dentro de uma janela de 24h (tamanho do histórico)
+pub const MAX_CLAIM_HISTORY: usize = 32;
+
+// Número máximo de usuários na blacklist (define o tamanho da conta)
+pub const MAX_BLACKLIST_SIZE: usize = 100;
+
+// Número máximo de usuários por chamada de add/remove_from_blacklist_batch
+pub const MAX_BLACKLIST_BATCH_SIZE: usize = 20;
+
+// Profundidade máxima da prova merkle da allowlist (até 2^20 carteiras)
+pub const MAX_MERKLE_PROOF_LEN: usize = 20;
+
+// Limites personalizados acima deste múltiplo do limite padrão do token
+// exigem timelock (AdminActionType::SetUserLimit)
+pub const LARGE_USER_LIMIT_MULTIPLIER: u64 = 10;
+
+// Avanços do nonce de claims acima deste valor exigem timelock
+// (AdminActionType::ResetUserNonce)
+pub const MAX_NONCE_RESET_JUMP: u64 = 1_000;
+
+// Número máximo de guardians que podem aprovar a troca emergencial de admin
+pub const MAX_GUARDIANS: usize = 10;
+
+// Taxa máxima de claim destinada ao tesouro (10%)
+pub const MAX_CLAIM_FEE_BPS: u16 = 1_000;
+
+// Base para cálculos em basis points (10000 = 100%)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// Teto de sanidade para quantidades de claim/mint/burn. Valores perto de
+// u64::MAX não têm sentido econômico e deixam margem para multiplicar por
+// bps (taxas, multiplicadores) sem overflow em u64
+pub const MAX_REASONABLE_AMOUNT: u64 = u64::MAX / BPS_DENOMINATOR;
+
+// Aplicar bps a uma quantidade, sempre arredondando para baixo (floor).
+// Política única para todos os splits (taxas, queima, referral): a parte
+// calculada nunca ultrapassa a fração exata, então a soma das partes nunca
+// excede o total. Use split_bps quando o restante também for creditado.
+pub fn apply_bps(amount: u64, bps: u64) -> Result<u64> {
+    let scaled = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+
+    u64::try_from(scaled).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+// Dividir uma quantidade em (parte em bps, restante); parte + restante == amount
+pub fn split_bps(amount: u64, bps: u64) -> Result<(u64, u64)> {
+    require!(bps <= BPS_DENOMINATOR, ErrorCode::InvalidInput);
+    let part = apply_bps(amount, bps)?;
+    let rest = amount.checked_sub(part).ok_or(ErrorCode::MathOverflow)?;
+    Ok((part, rest))
+}
+
+// Quanto mintar no destino de um claim e quanto queimar em seguida da ATA do
+// claimer: para a própria carteira o bruto é mintado e a taxa queimada; para
+// outra carteira só o líquido é mintado e não há queima. Em ambos o supply
+// cresce apenas o líquido. A parte em escrow não é mintada agora
+pub fn claim_mint_amounts(
+    claim_amount: u64,
+    burn_amount: u64,
+    escrowed_amount: u64,
+    to_other_wallet: bool,
+) -> Result<(u64, u64)> {
+    let (gross, burn) = if to_other_wallet {
+        (claim_amount.checked_sub(burn_amount).ok_or(ErrorCode::MathOverflow)?, 0)
+    } else {
+        (claim_amount, burn_amount)
+    };
+    let minted = gross.checked_sub(escrowed_amount).ok_or(ErrorCode::MathOverflow)?;
+    Ok((minted, burn))
+}
+
+// Transferir tokens para o tesouro do programa. Usado por depósitos e por
+// qualquer roteamento de taxas que precise creditar o cofre.
+pub fn transfer_to_treasury<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    treasury_token_account: &InterfaceAccount<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let transfer_ctx = CpiContext::new(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: from.to_account_info(),
+            mint: token_mint.to_account_info(),
+            to: treasury_token_account.to_account_info(),
+            authority,
+        },
+    );
+
+    token_interface::transfer_checked(transfer_ctx, amount, token_mint.decimals)
+}
+
+// Calcular o limite diário efetivo aplicando a curva de emissão (halving)
+pub fn effective_max_claim(base: u64, emission: &EmissionConfig, now: i64) -> Result<u64> {
+    let scaled = (base as u128)
+        .checked_mul(emission.initial_rate as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+
+    // halving_interval_seconds == 0 desativa o halving
+    if emission.halving_interval_seconds <= 0 || now <= emission.genesis_ts {
+        return u64::try_from(scaled).map_err(|_| error!(ErrorCode::MathOverflow));
+    }
+
+    let halvings = (now - emission.genesis_ts) / emission.halving_interval_seconds;
+    let effective = if halvings >= 128 { 0 } else { scaled >> halvings };
+
+    u64::try_from(effective).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+// Aplicar um multiplicador em bps a um limite
+pub fn apply_multiplier(limit: u64, multiplier_bps: u16) -> Result<u64> {
+    apply_bps(limit, multiplier_bps as u64)
+}
+
+// Calcular os limites (diário, horário) efetivos de um usuário
+pub fn claim_limits(
+    config: &ConfigAccount,
+    max_claim_per_user: u64,
+    role: Option<&ClaimRole>,
+    now: i64,
+) -> Result<(u64, u64)> {
+    // O limite diário é escalado pela curva de emissão
+    let max_daily = effective_max_claim(max_claim_per_user, &config.emission, now)?;
+    // Máximo por hora (1/24 do diário), arredondado para cima para que limites
+    // diários menores que 24 ainda permitam claimar; o limite diário continua valendo
+    let max_hourly = max_daily.div_ceil(24);
+
+    // Aplicar o papel do usuário, se existir
+    match role {
+        Some(role) if role.unlimited => Ok((u64::MAX, u64::MAX)),
+        Some(role) => Ok((
+            apply_multiplier(max_daily, role.limit_multiplier)?,
+            apply_multiplier(max_hourly, role.limit_multiplier)?,
+        )),
+        None => Ok((max_daily, max_hourly)),
+    }
+}
+
+// Limites de uma categoria de recompensa. A categoria padrão usa o limite
+// diário absoluto do usuário, se definido pelo admin, ou os limites gerais
+// (emissão + papel); as demais têm apenas o limite diário próprio
+pub fn category_claim_limits(
+    config: &ConfigAccount,
+    max_claim_per_user: u64,
+    role: Option<&ClaimRole>,
+    custom_daily_limit: Option<u64>,
+    category: u8,
+    now: i64,
+) -> Result<(u64, u64)> {
+    if category == DEFAULT_CLAIM_CATEGORY {
+        return match custom_daily_limit {
+            Some(limit) => Ok((limit, limit.div_ceil(24))),
+            None => claim_limits(config, max_claim_per_user, role, now),
+        };
+    }
+
+    let cap = config
+        .category_daily_caps
+        .get(category as usize)
+        .copied()
+        .unwrap_or(0);
+    require!(cap > 0, ErrorCode::InvalidClaimCategory);
+    Ok((cap, u64::MAX))
+}
+
 // Definir evento para registrar queima de tokens
 #[event]
 pub struct TokenBurnEvent {
@@ -46,7 +506,10 @@ pub struct TokenBurnEvent {
     pub token_mint: Pubkey,
     pub amount: u64,
     pub description: String,
+    pub burn_reason_code: u8,  // Código estruturado do motivo (0 = não especificado)
+    pub reference_id: u64,     // Referência do integrador (pedido, item...); 0 se ausente
     pub timestamp: i64,
+    pub global_seq: u64,
 }
 
 // Definir evento para registrar mint de tokens
@@ -57,14 +520,105 @@ pub struct TokenMintEvent {
     pub amount: u64,
     pub recipient: Pubkey,
     pub timestamp: i64,
+    pub global_seq: u64,
+}
+
+// Evento de movimentação do tesouro (depósito ou saque emergencial)
+#[event]
+pub struct TreasuryEvent {
+    pub action: String,             // DEPOSIT, EMERGENCY_WITHDRAW, SWEEP_LAMPORTS ou WITHDRAW_LAMPORTS
+    pub account: Pubkey,            // Depositante ou destinatário do saque
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+    pub global_seq: u64,
+}
+
+// Evento do escrow de vesting
+#[event]
+pub struct EscrowEvent {
+    pub action: String,             // DEPOSIT ou RELEASE
+    pub user: Pubkey,
+    pub amount: u64,
+    pub unlock_at: i64,
+    pub timestamp: i64,
+    pub global_seq: u64,
+}
+
+// Evento de profiling de compute units (só emitido com a feature `profiling`)
+#[event]
+pub struct ComputeProfileEvent {
+    pub op: String,    // claim ou burn
+    pub consumed: u64, // Compute units consumidas pelo handler
 }
 
 // Definir evento para registrar claim de tokens
 #[event]
 pub struct TokenClaimEvent {
     pub claimer: Pubkey,
+    pub recipient: Pubkey,  // Carteira que recebeu os tokens (pode diferir do claimer)
     pub token_mint: Pubkey,
+    pub amount: u64,        // Quantidade solicitada (assinada pelo backend)
+    pub minted_amount: u64, // Quantidade efetivamente mintada (PartialFill pode ser menor)
+    pub is_first_claim: bool, // Primeiro claim do usuário (ativação) ou recorrente (retenção)
+    pub game_session_hash: [u8; 32], // Compromisso assinado com os dados off-chain do claim (disputas)
+    pub fee_amount: u64,    // Taxa mintada ao tesouro além do claim
+    pub escrowed_amount: u64, // Parte do claim retida no escrow de vesting (mintada em release_escrow)
+    pub timestamp: i64,
+    pub global_seq: u64,
+}
+
+// Evento com os limites restantes do usuário após cada claim
+#[event]
+pub struct ClaimLimitsEvent {
+    pub user: Pubkey,
+    pub category: u8,
+    pub daily_claimed: u64,
+    pub hourly_claimed: u64,
+    pub daily_remaining: u64,
+    pub hourly_remaining: u64,
+    pub global_seq: u64,
+}
+
+// Nonce de claims do usuário após um claim (próximo valor a ser assinado)
+#[event]
+pub struct NonceEvent {
+    pub user: Pubkey,
+    pub old_nonce: u64,
+    pub new_nonce: u64,
+    pub global_seq: u64,
+}
+
+// Início de uma nova janela fixa de limite do usuário durante um claim
+#[event]
+pub struct LimitResetEvent {
+    pub user: Pubkey,
+    pub window_type: String,        // WEEKLY (diário/horário são janelas deslizantes, sem reset)
+    pub reset_timestamp: i64,
+    pub global_seq: u64,
+}
+
+// Resultado de uma simulação de claim (não altera estado)
+#[event]
+pub struct ClaimSimulationEvent {
+    pub user: Pubkey,
     pub amount: u64,
+    pub would_succeed: bool,
+    pub failing_check: String,
+    pub daily_remaining: u64,
+    pub hourly_remaining: u64,
+    pub supply_remaining: u64,
+    pub timestamp: i64,
+}
+
+// Estado de uma ação administrativa pendente (check_admin_action)
+#[event]
+pub struct AdminActionStatusEvent {
+    pub admin: Pubkey,
+    pub action: String,
+    pub ready_at: i64,
+    pub executed: bool,
+    pub executable: bool,
     pub timestamp: i64,
 }
 
@@ -75,6 +629,7 @@ pub struct SecurityEvent {
     pub user: Pubkey,
     pub reason: String,
     pub timestamp: i64,
+    pub global_seq: u64,
 }
 
 #[event]
@@ -83,9 +638,11 @@ pub struct AdminActionEvent {
     pub action: String,
     pub details: String,
     pub timestamp: i64,
+    pub global_seq: u64,
 }
 
 #[account]
+#[derive(InitSpace, Default)]
 pub struct ConfigAccount {
     pub payment_token_mint: Pubkey,
     pub admin: Pubkey,
@@ -93,79 +650,1269 @@ pub struct ConfigAccount {
     pub max_claim_per_user: u64,    // Máximo que um usuário pode claim em 24h
     pub total_supply_limit: u64,     // Limite total de supply que pode ser mintado
     pub total_minted: u64,           // Total já mintado
+    pub allow_future_timestamps: bool, // Aceitar assinaturas com timestamp no futuro?
+    pub emission: EmissionConfig,    // Curva de emissão (halving) aplicada aos claims
+    pub global_seq: u64,             // Sequência global monotônica para indexadores
+    pub config_bump: u8,             // Bump do PDA [b"config"]
+    pub mint_authority_bump: u8,     // Bump do PDA [b"mint_authority"]
+    pub claim_burn_bps: u16,         // Fração de cada claim queimada (bps, 0 = desativado)
+    pub enforce_mint_supply: bool,   // Usar o supply real do mint como limite rígido
+    pub min_claim_amount: u64,       // Mínimo por transação de claim (anti-dust)
+    pub max_claim_amount: u64,       // Máximo por transação de claim
+    pub allowlist_enabled: bool,     // Apenas usuários na allowlist podem claimar
+    pub clawback_window_seconds: i64, // Janela após um claim em que o clawback é permitido (0 = desativado)
+    pub max_batch_size: u16,         // Máximo de destinatários por batch_mint_tokens
+    #[max_len(MAX_PAUSE_REASON_LEN)]
+    pub pause_reason: String,        // Motivo da pausa atual (vazio se não pausado)
+    pub paused_at: i64,              // Quando a pausa atual começou (0 se não pausado)
+    pub account_age_requirement_seconds: i64, // Idade mínima do registro do usuário para claimar
+    pub claims_in_current_minute: u32, // Claims na janela de 1 minuto atual (circuit breaker)
+    pub minute_window_start: i64,    // Início da janela de 1 minuto atual
+    pub claims_per_minute_threshold: u32, // Claims por minuto que disparam a pausa automática (0 = desativado)
+    #[max_len(MAX_GUARDIANS)]
+    pub guardians: Vec<Pubkey>,      // Guardians da troca emergencial de admin (até MAX_GUARDIANS)
+    pub guardian_threshold: u8,      // Assinaturas de guardians exigidas (M-of-N, 0 = desativado)
+    pub treasury_bump: u8,           // Bump do PDA [b"treasury"] (0 = tesouro não inicializado)
+    pub category_daily_caps: [u64; MAX_CLAIM_CATEGORIES], // Limite diário por categoria (0 = categoria desativada)
+    pub max_admin_mint_per_call: u64, // Máximo por mint administrativo (alterado apenas via timelock)
+    pub verbose_logging: bool,       // Logs decorativos (banners) ligados? Desligar em mainnet
+    pub decimals: u8,                // Decimals do mint principal (limites são em unidades base)
+    pub reject_same_slot_claims: bool, // Rejeitar um segundo claim do usuário no mesmo slot?
+    pub merkle_root: [u8; 32],       // Raiz da allowlist merkle (zeros = desativada)
+    pub max_claim_per_week: u64,     // Máximo por usuário em 7 dias no token principal (0 = sem limite)
+    pub allow_admin_mint_while_paused: bool, // Mints administrativos de recuperação durante a pausa?
+    pub mint_authority: Pubkey,      // PDA [b"mint_authority"] esperado como authority dos mints de claim
+    pub claim_fee_bps: u16,          // Taxa mintada ao tesouro sobre cada claim (bps, 0 = desativado)
+    pub pause_expires_at: i64,       // Fim automático da pausa atual (0 = indefinida)
+    pub instant_claim_threshold: u64, // Líquido entregue na hora por claim; o excedente vai ao escrow (0 = desativado)
+    pub escrow_release_delay: i64,   // Segundos até o escrow poder ser liberado
+    pub backend_authority: Pubkey,   // Chave do backend que assina claims e queimas
+}
+
+impl ConfigAccount {
+    // Pausa efetiva: uma pausa com prazo deixa de valer depois de pause_expires_at
+    pub fn is_paused(&self, now: i64) -> bool {
+        self.emergency_paused && (self.pause_expires_at == 0 || now <= self.pause_expires_at)
+    }
+
+    // Avançar a sequência global e retornar o novo valor
+    pub fn next_seq(&mut self) -> Result<u64> {
+        self.global_seq = self.global_seq.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(self.global_seq)
+    }
+
+    // Converter tokens inteiros para unidades base (amount * 10^decimals)
+    pub fn to_base_units(&self, whole_tokens: u64) -> Result<u64> {
+        10u64
+            .checked_pow(self.decimals as u32)
+            .and_then(|scale| whole_tokens.checked_mul(scale))
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    // Converter unidades base para tokens inteiros (arredondando para baixo)
+    pub fn from_base_units(&self, base_units: u64) -> Result<u64> {
+        10u64
+            .checked_pow(self.decimals as u32)
+            .map(|scale| base_units / scale)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    // Registrar um claim na janela de 1 minuto. Com a janela já no limite o
+    // claim não é contado: o sistema é pausado e retorna true, e o chamador
+    // emite o AUTO_PAUSE e encerra o claim sem mintar (com Ok, para a pausa
+    // persistir)
+    pub fn record_claim_for_circuit_breaker(&mut self, now: i64) -> Result<bool> {
+        if self.claims_per_minute_threshold == 0 {
+            return Ok(false);
+        }
+
+        if now - self.minute_window_start >= 60 {
+            self.claims_in_current_minute = 0;
+            self.minute_window_start = now;
+        }
+
+        if self.claims_in_current_minute >= self.claims_per_minute_threshold {
+            self.emergency_paused = true;
+            self.pause_reason = "Circuit breaker: claims per minute threshold exceeded".to_string();
+            self.paused_at = now;
+            self.pause_expires_at = 0;
+            return Ok(true);
+        }
+
+        self.claims_in_current_minute = self.claims_in_current_minute
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(false)
+    }
+
+    // A janela de 1 minuto atual atingiu o limite do circuit breaker
+    pub fn circuit_breaker_tripped(&self, now: i64) -> bool {
+        self.claims_per_minute_threshold > 0
+            && now - self.minute_window_start < 60
+            && self.claims_in_current_minute >= self.claims_per_minute_threshold
+    }
+
+    // (total_minted, supply_limit, max_claim_per_user) do mint de um claim: os
+    // do RegisteredToken ou, para o token principal, os da config
+    pub fn mint_limits(&self, registered: Option<&RegisteredToken>) -> (u64, u64, u64) {
+        match registered {
+            Some(token) => (token.total_minted, token.supply_limit, token.max_claim_per_user),
+            None => (self.total_minted, self.total_supply_limit, self.max_claim_per_user),
+        }
+    }
+
+    // Mint aceito pelo programa: o token principal ou um token registrado. O
+    // RegisteredToken [b"registered_token", mint] é a lista de mints aceitos
+    pub fn accepts_mint(&self, mint: &Pubkey, registered: Option<&RegisteredToken>) -> bool {
+        *mint == self.payment_token_mint || registered.is_some_and(|token| token.mint == *mint)
+    }
+
+    // PDA [b"mint_authority"] do programa, derivado do bump salvo
+    pub fn program_mint_authority(&self) -> Option<Pubkey> {
+        Pubkey::create_program_address(&[b"mint_authority", &[self.mint_authority_bump]], &crate::ID)
+            .ok()
+    }
+
+    // A mint authority configurada pode assinar um mint: o PDA do programa
+    // assina via seeds; depois de set_mint_authority para outra chave, essa
+    // chave precisa assinar a transação (ou o CPI do programa que a controla)
+    pub fn can_sign_mint(&self, authority: &AccountInfo) -> bool {
+        *authority.key == self.mint_authority
+            && (authority.is_signer || self.program_mint_authority() == Some(self.mint_authority))
+    }
+
+    // Contar guardians distintos que assinaram a transação (remaining_accounts)
+    pub fn count_guardian_signers(&self, accounts: &[AccountInfo]) -> usize {
+        let mut signed: Vec<Pubkey> = Vec::new();
+        for account in accounts {
+            if account.is_signer
+                && self.guardians.contains(account.key)
+                && !signed.contains(account.key)
+            {
+                signed.push(*account.key);
+            }
+        }
+        signed.len()
+    }
+}
+
+// Curva de emissão: o limite por usuário é escalado por initial_rate (bps)
+// e dividido pela metade a cada halving_interval_seconds desde genesis_ts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Default)]
+pub struct EmissionConfig {
+    pub initial_rate: u64,              // Multiplicador inicial em bps (10000 = 1x)
+    pub halving_interval_seconds: i64,  // Intervalo entre halvings (0 = desativado)
+    pub genesis_ts: i64,                // Início da contagem dos halvings
 }
 
 // Conta para rastrear claims por usuário
 #[account]
+#[derive(InitSpace, Default)]
 pub struct UserClaimAccount {
     pub user: Pubkey,              // Usuário
     pub total_claimed: u64,         // Total já claimado por este usuário
     pub last_claim_timestamp: i64,  // Timestamp do último claim
-    pub daily_claimed: u64,         // Total claimado nas últimas 24h
-    pub daily_reset_timestamp: i64, // Quando o contador diário foi resetado
-    pub hourly_claimed: u64,        // Total claimado na última hora
-    pub hourly_reset_timestamp: i64, // Quando o contador horário foi resetado
-    pub nonce: u64,                 // Nonce para prevenir replay attacks
+    pub daily_claimed: u64,         // Total claimado nas 24h anteriores ao último claim
+    pub hourly_claimed: u64,        // Total claimado na hora anterior ao último claim
+    pub claim_nonce: u64,           // Nonce dos claims (incluído na mensagem assinada)
     pub is_blacklisted: bool,       // Usuário banido?
+    pub last_claim_amount: u64,     // Quantidade líquida do último claim (base do clawback)
+    pub initialized: bool,          // Conta já inicializada pelo primeiro claim?
+    #[max_len(MAX_CLAIM_HISTORY)]
+    pub claim_history: Vec<ClaimEntry>, // Claims das últimas 24h (janela deslizante)
+    pub custom_daily_limit: Option<u64>, // Limite diário absoluto definido pelo admin (substitui o padrão)
+    pub last_claim_slot: u64,       // Slot do último claim (timestamps se repetem dentro do slot)
+    pub bump: u8,                   // Bump canônico do PDA [b"user_claim", user, (mint)]
+    pub weekly_claimed: u64,        // Total claimado na janela semanal atual
+    pub weekly_reset_timestamp: i64, // Início da janela semanal atual
+    pub appeal_requested: bool,     // Usuário banido pediu revisão (request_unban)?
+    pub appeal_timestamp: i64,      // Quando o pedido de revisão foi feito
+    pub suspended_until: i64,       // Claims suspensos até este timestamp (0 = sem suspensão)
+    pub last_claim_mint: Pubkey,    // Mint do último claim (base do clawback)
+    pub last_claim_recipient: Pubkey, // Carteira que recebeu o último claim (base do clawback)
+}
+
+// Entrada do histórico de claims usado pela janela deslizante
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct ClaimEntry {
+    pub timestamp: i64,
+    pub amount: u64,
+    pub category: u8,
+}
+
+impl UserClaimAccount {
+    // Descartar entradas fora da janela de 24h
+    pub fn prune_claim_history(&mut self, now: i64) {
+        self.claim_history
+            .retain(|entry| now - entry.timestamp < ONE_DAY_SECONDS);
+    }
+
+    // Soma dos claims de uma categoria nos últimos `window` segundos
+    pub fn claimed_within(&self, now: i64, window: i64, category: u8) -> Result<u64> {
+        self.claim_history
+            .iter()
+            .filter(|entry| entry.category == category && now - entry.timestamp < window)
+            .try_fold(0u64, |total, entry| total.checked_add(entry.amount))
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    // Zerar as janelas diária/horária de uma categoria; as demais categorias e
+    // a janela semanal não mudam
+    pub fn reset_category_window(&mut self, category: u8) {
+        self.claim_history.retain(|entry| entry.category != category);
+        if category == DEFAULT_CLAIM_CATEGORY {
+            self.daily_claimed = 0;
+            self.hourly_claimed = 0;
+        }
+    }
+
+    // Total claimado na janela semanal, zero se a janela já expirou
+    pub fn claimed_this_week(&self, now: i64) -> u64 {
+        if now - self.weekly_reset_timestamp >= ONE_WEEK_SECONDS {
+            0
+        } else {
+            self.weekly_claimed
+        }
+    }
+}
+
+// Nonce de queimas por usuário, independente do nonce de claims para que
+// claims e queimas pré-assinados não invalidem um ao outro
+#[account]
+#[derive(InitSpace)]
+pub struct UserBurnAccount {
+    pub user: Pubkey,
+    pub burn_nonce: u64, // Nonce das queimas (incluído na mensagem assinada)
+}
+
+// Métricas agregadas do protocolo, para dashboards não precisarem varrer eventos
+#[account]
+#[derive(InitSpace)]
+pub struct StatsAccount {
+    pub unique_claimers: u64, // Usuários que já claimaram pelo menos uma vez (conta base)
+    pub lifetime_claims: u64, // Total de claims processados
+    pub lifetime_burns: u64,  // Total de queimas (burn_tokens / burn_from_delegate)
+    pub lifetime_mints: u64,  // Total de mints administrativos (mint_tokens / batch)
+}
+
+impl StatsAccount {
+    // Contabilizar um claim; `new_claimer` só no primeiro claim da conta base do usuário
+    pub fn record_claim(&mut self, new_claimer: bool) -> Result<()> {
+        if new_claimer {
+            self.unique_claimers = self.unique_claimers.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        }
+        self.lifetime_claims = self.lifetime_claims.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_burn(&mut self) -> Result<()> {
+        self.lifetime_burns = self.lifetime_burns.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_mint(&mut self) -> Result<()> {
+        self.lifetime_mints = self.lifetime_mints.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// Papel de claim por usuário (parceiros, contas de teste internas)
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimRole {
+    pub user: Pubkey,
+    pub limit_multiplier: u16,      // Multiplicador dos limites em bps (10000 = 1x)
+    pub unlimited: bool,            // Ignora os limites diário/horário
+    pub fee_exempt: bool,           // Isento da taxa do tesouro (claim_fee_bps)
+}
+
+// Sessão de gameplay já usada em um claim (prova de gameplay). O init do
+// PDA falha se a mesma sessão for claimada de novo, com qualquer quantidade.
+// Depois de SESSION_RETENTION_SECONDS o claimer pode fechá-lo; o backend não
+// deve assinar claims de sessões mais antigas que a retenção
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimedSession {
+    pub claimer: Pubkey,
+    pub session_hash: [u8; 32],
+    pub claimed_at: i64,
+    pub bump: u8,                   // Bump canônico do PDA [b"claimed_session", session_hash]
+}
+
+impl ClaimedSession {
+    pub fn closable(&self, now: i64) -> bool {
+        now - self.claimed_at >= SESSION_RETENTION_SECONDS
+    }
+}
+
+// Escrow de vesting por usuário: parte de claims grandes que só é mintada
+// depois de unlock_at (release_escrow). Já conta em total_minted no claim
+#[account]
+#[derive(InitSpace)]
+pub struct EscrowAccount {
+    pub user: Pubkey,
+    pub amount: u64,                // Total retido aguardando liberação
+    pub unlock_at: i64,             // Novos depósitos estendem o prazo de todo o saldo
+    pub bump: u8,
+}
+
+// Token adicional aceito pelo programa, com supply e limites próprios
+#[account]
+#[derive(InitSpace)]
+pub struct RegisteredToken {
+    pub mint: Pubkey,
+    pub supply_limit: u64,          // Limite de supply deste token
+    pub total_minted: u64,          // Total já mintado deste token
+    pub max_claim_per_user: u64,    // Máximo por usuário em 24h deste token
+}
+
+// Registro do usuário, usado para exigir uma idade mínima antes do
+// primeiro claim (anti-sybil)
+#[account]
+#[derive(InitSpace)]
+pub struct UserRegistration {
+    pub user: Pubkey,
+    pub registered_at: i64,
+}
+
+// Entrada da allowlist (modo beta privado)
+#[account]
+#[derive(InitSpace)]
+pub struct AllowlistEntry {
+    pub user: Pubkey,
+    pub added_at: i64,
 }
 
 // Lista negra de usuários
 #[account]
+#[derive(InitSpace)]
 pub struct BlacklistAccount {
     pub admin: Pubkey,
+    #[max_len(MAX_BLACKLIST_SIZE)]
     pub blacklisted_users: Vec<Pubkey>,
+    pub bump: u8,                   // Bump canônico do PDA [b"blacklist"]
+}
+
+// Banimento temporário de um usuário, PDA [b"blacklist_entry", user]. Depois
+// de expires_at deixa de valer e qualquer um pode fechá-lo (prune_blacklist_entry)
+#[account]
+#[derive(InitSpace)]
+pub struct BlacklistEntry {
+    pub user: Pubkey,
+    pub expires_at: i64,            // Fim do banimento
+    pub created_at: i64,
+    pub bump: u8,                   // Bump canônico do PDA [b"blacklist_entry", user]
+}
+
+impl BlacklistEntry {
+    pub fn expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
 }
 
 // Conta para operações administrativas com delay
 #[account]
+#[derive(InitSpace)]
 pub struct PendingAdminAction {
     pub action_type: AdminActionType,
     pub new_value: Pubkey,          // Novo valor (admin, token, etc.)
+    pub new_amount: u64,            // Novo valor numérico (SetAdminMintCap, ChangeMaxClaim, ChangeSupplyLimit)
     pub requested_at: i64,         // Quando foi solicitado
     pub executed: bool,            // Já foi executado?
+    pub bump: u8,                  // Bump canônico do PDA [b"pending_action", admin]
+    pub ready_at: i64,             // requested_at + ADMIN_ACTION_DELAY_SECONDS
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+// Os discriminantes são fixos e gravados em PendingAdminAction: nunca
+// reutilizar ou renumerar um valor existente, apenas adicionar novos.
+// A serialização é manual porque o derive do Borsh usa a ordem de declaração.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
 pub enum AdminActionType {
-    ChangeAdmin,
-    ChangeToken,
-    EmergencyWithdraw,
+    ChangeAdmin = 0,
+    ChangeToken = 1,
+    EmergencyWithdraw = 2,
+    ChangeMintAuthority = 3,
+    SetAdminMintCap = 4,
+    CloseConfig = 5,
+    ChangeMaxClaim = 6,
+    ChangeSupplyLimit = 7,
+    SetUserLimit = 8,
+    ResetUserNonce = 9,
 }
 
-#[program]
-pub mod playtoearn_program {
-    use super::*;
+// Serializado como um único u8
+impl Space for AdminActionType {
+    const INIT_SPACE: usize = 1;
+}
 
-    pub fn initialize_config(
-        ctx: Context<InitializeConfig>,
-        payment_token_mint: Pubkey,
-        max_claim_per_user: u64,
-        total_supply_limit: u64,
-    ) -> Result<()> {
-        msg!("=== INITIALIZE CONFIG ===");
-        msg!("Payment Token Mint: {}", payment_token_mint);
-        msg!("Max Claim Per User: {}", max_claim_per_user);
-        msg!("Total Supply Limit: {}", total_supply_limit);
+impl AnchorSerialize for AdminActionType {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        (*self as u8).serialize(writer)
+    }
+}
 
-        // Validar entrada
-        require!(payment_token_mint != Pubkey::default(), ErrorCode::InvalidInput);
-        require!(max_claim_per_user > 0, ErrorCode::InvalidInput);
-        require!(total_supply_limit > 0, ErrorCode::InvalidInput);
+impl AnchorDeserialize for AdminActionType {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        match u8::deserialize_reader(reader)? {
+            0 => Ok(AdminActionType::ChangeAdmin),
+            1 => Ok(AdminActionType::ChangeToken),
+            2 => Ok(AdminActionType::EmergencyWithdraw),
+            3 => Ok(AdminActionType::ChangeMintAuthority),
+            4 => Ok(AdminActionType::SetAdminMintCap),
+            5 => Ok(AdminActionType::CloseConfig),
+            6 => Ok(AdminActionType::ChangeMaxClaim),
+            7 => Ok(AdminActionType::ChangeSupplyLimit),
+            8 => Ok(AdminActionType::SetUserLimit),
+            9 => Ok(AdminActionType::ResetUserNonce),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Unknown AdminActionType discriminant",
+            )),
+        }
+    }
+}
 
-        // Configurar a conta
-        let config = &mut ctx.accounts.config;
+// Modo de claim: AllOrNothing rejeita claims acima do limite, PartialFill
+// minta apenas o que ainda cabe nos limites do usuário e no supply
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClaimMode {
+    #[default]
+    AllOrNothing,
+    PartialFill,
+}
+
+// Mensagem JSON que o backend assina para um claim, com o nonce atual do usuário
+pub fn claim_message(claimer: &Pubkey, token_mint: &Pubkey, args: &ClaimArgs, nonce: u64) -> String {
+    let recipient = if args.recipient == Pubkey::default() { *claimer } else { args.recipient };
+    format!(
+        "{{\"wallet\":\"{}\",\"recipient\":\"{}\",\"token_mint\":\"{}\",\"amount\":{},\"timestamp\":\"{}\",\"nonce\":{},\"expiry_ts\":{},\"category\":{},\"game_session_hash\":\"{}\",\"action\":\"claim\"}}",
+        claimer,
+        recipient,
+        token_mint,
+        args.amount,
+        args.timestamp,
+        nonce,
+        args.expiry_ts,
+        args.category,
+        args.game_session_hash.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+    )
+}
+
+// Mensagem JSON que o backend assina para uma queima, com o nonce de queimas do usuário
+pub fn burn_message(
+    payer: &Pubkey,
+    token_mint: &Pubkey,
+    amount: u64,
+    timestamp: i64,
+    nonce: u64,
+    reason_code: u8,
+    reference_id: u64,
+) -> String {
+    format!(
+        "{{\"wallet\":\"{}\",\"token_mint\":\"{}\",\"amount\":{},\"timestamp\":\"{}\",\"nonce\":{},\"reason_code\":{},\"reference_id\":{},\"action\":\"burn\"}}",
+        payer, token_mint, amount, timestamp, nonce, reason_code, reference_id,
+    )
+}
+
+// Mensagem JSON de uma queima por delegate, que autoriza aquele delegate específico
+pub fn delegated_burn_message(
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    token_mint: &Pubkey,
+    amount: u64,
+    timestamp: i64,
+    nonce: u64,
+) -> String {
+    format!(
+        "{{\"wallet\":\"{}\",\"delegate\":\"{}\",\"token_mint\":\"{}\",\"amount\":{},\"timestamp\":\"{}\",\"nonce\":{},\"action\":\"burn_from_delegate\"}}",
+        owner, delegate, token_mint, amount, timestamp, nonce,
+    )
+}
+
+// Parâmetros de um claim, comuns a claim_tokens, claim_tokens_merkle e
+// claim_tokens_batched. `recipient` = Pubkey::default() envia ao próprio claimer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClaimArgs {
+    pub amount: u64,
+    pub timestamp: i64,
+    pub signature: [u8; 64],
+    pub mode: ClaimMode,
+    pub recipient: Pubkey,
+    pub expiry_ts: i64,
+    pub category: u8,
+    pub game_session_hash: [u8; 32],
+}
+
+// Raiz assinada de um lote de claims e a prova da folha deste claim
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClaimBatchProof {
+    pub root: [u8; 32],
+    pub proof: Vec<[u8; 32]>,
+}
+
+// Versão atual do formato de ConfigView. Campos novos só entram com uma
+// nova versão; os existentes nunca mudam de posição ou tipo
+pub const CONFIG_VIEW_VERSION: u8 = 1;
+
+// Resultado de get_config: interface estável da config para clientes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct ConfigView {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub payment_token_mint: Pubkey,
+    pub decimals: u8,
+    pub emergency_paused: bool,
+    pub max_claim_per_user: u64,
+    pub max_claim_per_week: u64,
+    pub min_claim_amount: u64,
+    pub max_claim_amount: u64,
+    pub total_supply_limit: u64,
+    pub total_minted: u64,
+    pub claim_burn_bps: u16,
+    pub claim_fee_bps: u16,
+    pub allowlist_enabled: bool,
+}
+
+// Resultado de is_blacklisted: resposta única e as fontes que marcaram o usuário
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct BlacklistStatus {
+    pub is_blacklisted: bool,
+    pub in_blacklist_account: bool, // Presente no Vec de BlacklistAccount
+    pub user_claim_flag: bool,      // Flag is_blacklisted da UserClaimAccount
+    pub temporary_ban: bool,        // BlacklistEntry ainda não vencida
+}
+
+// Corpo comum de claim_tokens e claim_tokens_merkle. Fica fora do módulo do
+// programa porque o Anchor trata toda função com Context ali como instrução.
+// `merkle_proof` presente exige prova válida contra config.merkle_root;
+// `batch` presente troca a assinatura do claim pela assinatura da raiz do lote
+fn process_claim(
+    ctx: Context<ClaimTokens>,
+    args: ClaimArgs,
+    merkle_proof: Option<&[[u8; 32]]>,
+    batch: Option<&ClaimBatchProof>,
+) -> Result<()> {
+    let ClaimArgs {
+        amount,
+        timestamp,
+        signature,
+        mode,
+        recipient,
+        expiry_ts,
+        category,
+        game_session_hash,
+    } = args;
+
+    #[cfg(feature = "profiling")]
+    let cu_start = anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
+
+    // Pubkey::default() significa enviar para o próprio claimer
+    let claimer_key = ctx.accounts.claimer.key();
+    let recipient = if recipient == Pubkey::default() { claimer_key } else { recipient };
+
+    #[cfg(not(feature = "structured-logs"))]
+    if ctx.accounts.config.verbose_logging {
+        msg!("=== CLAIM TOKENS ===");
+        msg!("Amount: {}", amount);
+        msg!("User: {}", claimer_key);
+        msg!("Recipient: {}", recipient);
+    }
+
+    require!(
+        !ctx.accounts.config.is_paused(Clock::get()?.unix_timestamp),
+        ErrorCode::SystemPaused
+    );
+    require!(amount > 0, ErrorCode::InvalidPaymentAmount);
+    require!(amount <= MAX_REASONABLE_AMOUNT, ErrorCode::InvalidPaymentAmount);
+    require!(
+        amount <= ctx.accounts.config.max_claim_amount,
+        ErrorCode::InvalidPaymentAmount
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+
+    // Banimento e suspensão ficam na conta principal [b"user_claim", claimer]
+    // e valem para todos os tokens; em claims de token registrado ela é lida
+    // de user_status (as contas por mint só guardam contadores)
+    let (is_flagged, suspended_until) = if ctx.accounts.registered_token.is_some() {
+        match load_user_claim(&ctx.accounts.user_status)? {
+            Some(status) => (status.is_blacklisted, status.suspended_until),
+            None => (false, 0),
+        }
+    } else {
+        let user_claim = &ctx.accounts.user_claim_account;
+        (user_claim.is_blacklisted, user_claim.suspended_until)
+    };
+
+    // Verificar se usuário não está na blacklist (flag e lista autoritativa,
+    // ambas permanentes) nem com um banimento temporário em vigor. Uma
+    // BlacklistEntry vencida não bloqueia mesmo antes de ser podada
+    require!(!is_flagged, ErrorCode::Unauthorized);
+    require!(
+        !blacklist_contains(&ctx.accounts.blacklist, &ctx.accounts.claimer.key())?,
+        ErrorCode::Unauthorized
+    );
+    require!(
+        !blacklist_entry_active(&ctx.accounts.blacklist_entry, now)?,
+        ErrorCode::Unauthorized
+    );
+
+    // Suspensão temporária (mais leve que a blacklist); deixa de valer ao vencer
+    require!(now >= suspended_until, ErrorCode::UserSuspended);
+
+    // A prova merkle, quando enviada, precisa bater com a raiz atual (uma
+    // prova contra uma raiz antiga é rejeitada)
+    if let Some(proof) = merkle_proof {
+        let root = ctx.accounts.config.merkle_root;
+        require!(root != [0u8; 32], ErrorCode::InvalidMerkleProof);
+        require!(
+            verify_merkle_proof(keccak::hashv(&[claimer_key.as_ref()]).0, proof, &root),
+            ErrorCode::InvalidMerkleProof
+        );
+    }
+
+    // Com a allowlist ativa, o usuário precisa apresentar sua entrada ou a prova merkle
+    if ctx.accounts.config.allowlist_enabled {
+        require!(
+            ctx.accounts.allowlist_entry.is_some() || merkle_proof.is_some(),
+            ErrorCode::NotAllowlisted
+        );
+    }
+
+    // Exigir idade mínima do registro do usuário (anti-sybil)
+    let age_requirement = ctx.accounts.config.account_age_requirement_seconds;
+    if age_requirement > 0 {
+        let registration = ctx.accounts.user_registration.as_ref()
+            .ok_or(ErrorCode::AccountTooYoung)?;
+        require!(
+            Clock::get()?.unix_timestamp - registration.registered_at >= age_requirement,
+            ErrorCode::AccountTooYoung
+        );
+    }
+
+    // Destino diferente do claimer exige a ATA do destinatário para o mint
+    let to_other_wallet = recipient != claimer_key;
+    if to_other_wallet {
+        let recipient_token_account = ctx.accounts.recipient_token_account.as_ref()
+            .ok_or(ErrorCode::InvalidTokenAccount)?;
+        require_keys_eq!(recipient_token_account.owner, recipient, ErrorCode::InvalidTokenAccount);
+        require_keys_eq!(
+            recipient_token_account.key(),
+            get_associated_token_address_with_program_id(
+                &recipient,
+                &ctx.accounts.token_mint.key(),
+                &ctx.accounts.token_program.key(),
+            ),
+            ErrorCode::InvalidTokenAccount
+        );
+    }
+
+    // Verificar assinatura do backend (o destino e o mint também são autorizados
+    // pelo backend, então uma assinatura não vale para outro token)
+    let message = claim_message(
+        &claimer_key,
+        &ctx.accounts.token_mint.key(),
+        &args,
+        ctx.accounts.user_claim_account.claim_nonce,
+    );
+    let message_bytes = message.as_bytes();
+
+    match batch {
+        // Lote: a assinatura cobre apenas a raiz; esta mensagem é uma folha dela
+        Some(batch) => {
+            require!(
+                verify_merkle_proof(keccak::hashv(&[message_bytes]).0, &batch.proof, &batch.root),
+                ErrorCode::InvalidMerkleProof
+            );
+            let root_message = format!(
+                "{{\"batch_root\":\"{}\",\"action\":\"claim_batch\"}}",
+                batch.root.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+            );
+            verify_signature(
+                &ctx.accounts.sysvar_instructions,
+                root_message.as_bytes(),
+                &signature,
+                &ctx.accounts.backend_authority.key(),
+                &claimer_key,
+            )?;
+        }
+        None => verify_signature(
+            &ctx.accounts.sysvar_instructions,
+            message_bytes,
+            &signature,
+            &ctx.accounts.backend_authority.key(),
+            &claimer_key,
+        )?,
+    }
+
+    // Vouchers (expiry_ts > 0) valem até expiry_ts em vez da janela de 5
+    // minutos; payloads comuns (expiry_ts == 0) seguem a janela normal
+    if expiry_ts > 0 {
+        require!(now <= expiry_ts, ErrorCode::VoucherExpired);
+        if timestamp > now {
+            validate_signature_timestamp(
+                now,
+                timestamp,
+                ctx.accounts.config.allow_future_timestamps,
+                &claimer_key,
+            )?;
+        }
+    } else {
+        // Verificar timestamp (5 minutos de tolerância, apenas passado por padrão)
+        validate_signature_timestamp(
+            now,
+            timestamp,
+            ctx.accounts.config.allow_future_timestamps,
+            &claimer_key,
+        )?;
+    }
+
+    // Hash zero = claim sem prova de gameplay, sem ClaimedSession; com hash,
+    // o PDA da sessão é obrigatório (o init rejeita sessões repetidas)
+    require!(
+        ctx.accounts.claimed_session.is_some() == (game_session_hash != NO_GAME_SESSION),
+        ErrorCode::InvalidInput
+    );
+
+    // Circuit breaker: só conta claims já autenticados, para que um caller
+    // sem assinatura ou banido não consiga esgotar a janela. O claim que
+    // estoura o limite pausa o sistema e termina sem mintar; o nonce não
+    // avança e a sessão de gameplay é liberada, então o mesmo claim assinado
+    // pode ser reenviado depois do emergency_resume (se ainda não expirou)
+    if ctx.accounts.config.record_claim_for_circuit_breaker(now)? {
+        let config = &mut ctx.accounts.config;
+        emit!(SecurityEvent {
+            event_type: "AUTO_PAUSE".to_string(),
+            user: claimer_key,
+            reason: format!(
+                "{} claims in the current minute (threshold {})",
+                config.claims_in_current_minute, config.claims_per_minute_threshold
+            ),
+            timestamp: now,
+            global_seq: config.next_seq()?,
+        });
+
+        #[cfg(not(feature = "structured-logs"))]
+        if config.verbose_logging {
+            msg!("⚠️ CIRCUIT BREAKER ATIVADO - SISTEMA PAUSADO");
+        }
+
+        if let Some(claimed_session) = &ctx.accounts.claimed_session {
+            claimed_session.close(ctx.accounts.claimer.to_account_info())?;
+        }
+        return Ok(());
+    }
+
+    // Limites do token escolhido: registro próprio ou a config (token principal)
+    let (total_minted, supply_limit, max_claim_per_user) = ctx
+        .accounts
+        .config
+        .mint_limits(ctx.accounts.registered_token.as_deref());
+
+    // Registrar a sessão de gameplay, se houver
+    if let Some(claimed_session) = ctx.accounts.claimed_session.as_mut() {
+        claimed_session.claimer = claimer_key;
+        claimed_session.session_hash = game_session_hash;
+        claimed_session.claimed_at = now;
+        claimed_session.bump = ctx.bumps.claimed_session.ok_or(ErrorCode::InvalidInput)?;
+    }
+
+    // Verificar limites por usuário
+    let user_claim = &mut ctx.accounts.user_claim_account;
+
+    // Inicializar conta se for nova (primeiro claim do usuário)
+    let is_first_claim = !user_claim.initialized;
+    if is_first_claim {
+        user_claim.initialized = true;
+        user_claim.user = ctx.accounts.claimer.key();
+        user_claim.bump = ctx.bumps.user_claim_account;
+        user_claim.total_claimed = 0;
+        user_claim.last_claim_timestamp = 0;
+        user_claim.daily_claimed = 0;
+        user_claim.hourly_claimed = 0;
+        user_claim.claim_nonce = 0;
+        user_claim.is_blacklisted = false;
+        user_claim.last_claim_amount = 0;
+    }
+
+    // Suspensão já vencida (conferida acima): limpar no primeiro claim após o prazo
+    user_claim.suspended_until = 0;
+
+    // Dois claims no mesmo slot compartilham o mesmo `now`, o que anula
+    // cooldown e janelas; opcionalmente rejeitar o segundo
+    let slot = Clock::get()?.slot;
+    if ctx.accounts.config.reject_same_slot_claims && !is_first_claim {
+        require!(user_claim.last_claim_slot != slot, ErrorCode::SameSlotReplay);
+    }
+
+    // Janela deslizante: somar apenas os claims das últimas 24h / 1h, sem
+    // resets fixos (evita claimar o limite cheio duas vezes na virada)
+    user_claim.prune_claim_history(now);
+    let daily_claimed = user_claim.claimed_within(now, ONE_DAY_SECONDS, category)?;
+    let hourly_claimed = user_claim.claimed_within(now, ONE_HOUR_SECONDS, category)?;
+    require!(
+        user_claim.claim_history.len() < MAX_CLAIM_HISTORY,
+        ErrorCode::ClaimHistoryFull
+    );
+
+    // Verificar limites (curva de emissão + papel do usuário, ou o limite da categoria)
+    let (max_daily, max_hourly) = category_claim_limits(
+        &ctx.accounts.config,
+        max_claim_per_user,
+        ctx.accounts.claim_role.as_deref(),
+        user_claim.custom_daily_limit,
+        category,
+        now,
+    )?;
+
+    // Limite semanal (todas as categorias), apenas para o token principal
+    let max_weekly = match (&ctx.accounts.registered_token, ctx.accounts.config.max_claim_per_week) {
+        (None, cap) if cap > 0 => cap,
+        _ => u64::MAX,
+    };
+    let weekly_claimed = user_claim.claimed_this_week(now);
+
+    // Em PartialFill, claimar apenas o que cabe nos limites e no supply
+    let claim_amount = match mode {
+        ClaimMode::AllOrNothing => amount,
+        ClaimMode::PartialFill => {
+            let mut supply_remaining = supply_limit.saturating_sub(total_minted);
+            if ctx.accounts.config.enforce_mint_supply {
+                supply_remaining = supply_remaining
+                    .min(supply_limit.saturating_sub(ctx.accounts.token_mint.supply));
+            }
+            amount
+                .min(max_hourly.saturating_sub(hourly_claimed))
+                .min(max_daily.saturating_sub(daily_claimed))
+                .min(max_weekly.saturating_sub(weekly_claimed))
+                .min(supply_remaining)
+        }
+    };
+    require!(claim_amount > 0, ErrorCode::InvalidPaymentAmount);
+    require!(
+        claim_amount >= ctx.accounts.config.min_claim_amount,
+        ErrorCode::InvalidPaymentAmount
+    );
+
+    // Calcular a taxa de queima sobre o claim; o supply conta apenas o líquido
+    let (burn_amount, net_amount) =
+        split_bps(claim_amount, ctx.accounts.config.claim_burn_bps as u64)?;
+
+    // Taxa do tesouro: mintada além do claim e contada no supply. Usuários
+    // isentos pelo papel de claim não pagam (o evento registra fee_amount = 0)
+    let fee_amount = if ctx.accounts.claim_role.as_ref().is_some_and(|role| role.fee_exempt) {
+        0
+    } else {
+        apply_bps(claim_amount, ctx.accounts.config.claim_fee_bps as u64)?
+    };
+
+    // Vesting: no token principal, o líquido acima de instant_claim_threshold
+    // vai para o escrow do claimer em vez de ser mintado na hora
+    let instant_claim_threshold = ctx.accounts.config.instant_claim_threshold;
+    let escrowed_amount = if ctx.accounts.registered_token.is_none() && instant_claim_threshold > 0 {
+        net_amount.saturating_sub(instant_claim_threshold)
+    } else {
+        0
+    };
+
+    // Verificar limites de supply total
+    let new_total = total_minted
+        .checked_add(net_amount)
+        .and_then(|total| total.checked_add(fee_amount))
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(new_total <= supply_limit, ErrorCode::InvalidPaymentAmount);
+
+    // Rejeitar antes do CPI um mint que estouraria o u64 do SPL Token
+    let new_mint_supply = ctx.accounts.token_mint.supply
+        .checked_add(claim_amount)
+        .and_then(|supply| supply.checked_add(fee_amount))
+        .ok_or(ErrorCode::MintSupplyOverflow)?;
+
+    // O supply real do mint é o limite rígido (o contador interno pode divergir)
+    if ctx.accounts.config.enforce_mint_supply {
+        require!(new_mint_supply <= supply_limit, ErrorCode::InvalidPaymentAmount);
+    }
+
+    let new_hourly_total = hourly_claimed.checked_add(claim_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(new_hourly_total <= max_hourly, ErrorCode::InvalidPaymentAmount);
+
+    let new_daily_total = daily_claimed.checked_add(claim_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(new_daily_total <= max_daily, ErrorCode::InvalidPaymentAmount);
+
+    let new_weekly_total = weekly_claimed.checked_add(claim_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(new_weekly_total <= max_weekly, ErrorCode::WeeklyLimitExceeded);
+
+    // Atualizar dados do usuário
+    user_claim.total_claimed = user_claim.total_claimed.checked_add(claim_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    if category == DEFAULT_CLAIM_CATEGORY {
+        user_claim.daily_claimed = new_daily_total;
+        user_claim.hourly_claimed = new_hourly_total;
+    }
+    if now - user_claim.weekly_reset_timestamp >= ONE_WEEK_SECONDS {
+        user_claim.weekly_reset_timestamp = now;
+
+        emit!(LimitResetEvent {
+            user: claimer_key,
+            window_type: "WEEKLY".to_string(),
+            reset_timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+    }
+    user_claim.weekly_claimed = new_weekly_total;
+    user_claim.claim_history.push(ClaimEntry {
+        timestamp: now,
+        amount: claim_amount,
+        category,
+    });
+    user_claim.last_claim_timestamp = now;
+    user_claim.last_claim_slot = slot;
+    // Base do clawback: o que de fato chegou ao destinatário (sem a parte em escrow)
+    user_claim.last_claim_amount = net_amount - escrowed_amount;
+    user_claim.last_claim_mint = ctx.accounts.token_mint.key();
+    user_claim.last_claim_recipient = recipient;
+    let old_nonce = user_claim.claim_nonce;
+    user_claim.claim_nonce = old_nonce.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+    // Nonce autoritativo para o cliente pedir a próxima assinatura sem buscar a conta
+    emit!(NonceEvent {
+        user: claimer_key,
+        old_nonce,
+        new_nonce: user_claim.claim_nonce,
+        global_seq: ctx.accounts.config.next_seq()?,
+    });
+
+    // Usuário único só conta no primeiro claim da conta base [b"user_claim", user];
+    // as contas por mint de tokens registrados são do mesmo usuário
+    if let Some(stats) = ctx.accounts.stats.as_mut() {
+        stats.record_claim(is_first_claim && ctx.accounts.registered_token.is_none())?;
+    }
+
+    // Atualizar total mintado (do token registrado ou global)
+    if let Some(registered) = ctx.accounts.registered_token.as_mut() {
+        registered.total_minted = new_total;
+    } else {
+        ctx.accounts.config.total_minted = new_total;
+    }
+
+    // Depositar o excedente no escrow (o prazo vale para todo o saldo)
+    if escrowed_amount > 0 {
+        let unlock_at = now
+            .checked_add(ctx.accounts.config.escrow_release_delay)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let escrow_bump = ctx.bumps.escrow.ok_or(ErrorCode::InvalidInput)?;
+        let escrow = ctx.accounts.escrow.as_mut().ok_or(ErrorCode::InvalidInput)?;
+        escrow.user = claimer_key;
+        escrow.bump = escrow_bump;
+        escrow.amount = escrow.amount.checked_add(escrowed_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        escrow.unlock_at = unlock_at;
+
+        emit!(EscrowEvent {
+            action: "DEPOSIT".to_string(),
+            user: claimer_key,
+            amount: escrowed_amount,
+            unlock_at,
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+    }
+
+    // Checks-effects-interactions: o Anchor só serializa as contas no fim da
+    // instrução, então os contadores acima são gravados agora, antes dos CPIs
+    // abaixo; uma reentrada (ex. hooks do Token-2022) já encontra o nonce
+    // consumido. Depois deles só os eventos (e o global_seq deles)
+    ctx.accounts.exit(&crate::ID)?;
+
+    let config = &mut ctx.accounts.config;
+    let global_seq = config.next_seq()?;
+
+    // Mintar tokens (o PDA assina com o bump salvo na config; uma authority
+    // externa já assinou a transação)
+    let mint_authority_bump = config.mint_authority_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"mint_authority", &[mint_authority_bump]]];
+    let (destination_amount, burn_now) =
+        claim_mint_amounts(claim_amount, burn_amount, escrowed_amount, to_other_wallet)?;
+    let destination = match &ctx.accounts.recipient_token_account {
+        Some(recipient_token_account) if to_other_wallet => recipient_token_account.to_account_info(),
+        _ => ctx.accounts.claimer_token_account.to_account_info(),
+    };
+    let mint_to_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: destination,
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    mint_to(mint_to_ctx, destination_amount)?;
+
+    let mint_seq = config.next_seq()?;
+    emit!(TokenMintEvent {
+        minter: ctx.accounts.mint_authority.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        amount: destination_amount,
+        recipient,
+        timestamp: now,
+        global_seq: mint_seq,
+    });
+
+    // Queimar imediatamente a taxa de queima do claim (claimer assina)
+    if burn_now > 0 {
+        let burn_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.token_mint.to_account_info(),
+                from: ctx.accounts.claimer_token_account.to_account_info(),
+                authority: ctx.accounts.claimer.to_account_info(),
+            },
+        );
+
+        burn(burn_ctx, burn_now)?;
+
+        let burn_seq = config.next_seq()?;
+        emit!(TokenBurnEvent {
+            payer: ctx.accounts.claimer.key(),
+            token_mint: ctx.accounts.token_mint.key(),
+            amount: burn_now,
+            description: "claim burn tax".to_string(),
+            burn_reason_code: BURN_REASON_UNSPECIFIED,
+            reference_id: 0,
+            timestamp: now,
+            global_seq: burn_seq,
+        });
+
+        #[cfg(not(feature = "structured-logs"))]
+        if config.verbose_logging {
+            msg!("Burn Tax: {}", burn_now);
+        }
+    }
+
+    // Mintar a taxa na ATA canônica do tesouro
+    if fee_amount > 0 {
+        let treasury_token_account = ctx.accounts.treasury_token_account.as_ref()
+            .ok_or(ErrorCode::InvalidTokenAccount)?;
+        let treasury_authority =
+            Pubkey::create_program_address(&[b"treasury", &[config.treasury_bump]], &crate::ID)
+                .map_err(|_| error!(ErrorCode::InvalidTokenAccount))?;
+        require_keys_eq!(
+            treasury_token_account.key(),
+            get_associated_token_address_with_program_id(
+                &treasury_authority,
+                &ctx.accounts.token_mint.key(),
+                &ctx.accounts.token_program.key(),
+            ),
+            ErrorCode::InvalidTokenAccount
+        );
+
+        let mint_fee_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: treasury_token_account.to_account_info(),
+                authority: ctx.accounts.mint_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        mint_to(mint_fee_ctx, fee_amount)?;
+
+        #[cfg(not(feature = "structured-logs"))]
+        if config.verbose_logging {
+            msg!("Treasury Fee: {}", fee_amount);
+        }
+    }
+
+    // Emitir evento
+    emit!(TokenClaimEvent {
+        claimer: ctx.accounts.claimer.key(),
+        recipient,
+        token_mint: ctx.accounts.token_mint.key(),
+        amount,
+        minted_amount: claim_amount,
+        is_first_claim,
+        game_session_hash,
+        fee_amount,
+        escrowed_amount,
+        timestamp: now,
+        global_seq,
+    });
+
+    emit!(ClaimLimitsEvent {
+        user: ctx.accounts.claimer.key(),
+        category,
+        daily_claimed: new_daily_total,
+        hourly_claimed: new_hourly_total,
+        daily_remaining: max_daily.saturating_sub(new_daily_total),
+        hourly_remaining: max_hourly.saturating_sub(new_hourly_total),
+        global_seq: config.next_seq()?,
+    });
+
+    #[cfg(not(feature = "structured-logs"))]
+    if ctx.accounts.config.verbose_logging {
+        msg!("🎁 TOKENS CLAIMADOS COM SUCESSO!");
+        msg!("Amount: {}", claim_amount);
+        msg!("User: {}", ctx.accounts.claimer.key());
+        msg!("New Total Supply: {}", new_total);
+    }
+    #[cfg(feature = "structured-logs")]
+    msg!(&structured_log_line(
+        "claim",
+        &[
+            ("user", &claimer_key),
+            ("recipient", &recipient),
+            ("amount", &claim_amount),
+            ("burn", &burn_amount),
+            ("total", &new_total),
+            ("seq", &global_seq),
+        ],
+    ));
+
+    #[cfg(feature = "profiling")]
+    emit_compute_profile("claim", cu_start);
+
+    Ok(())
+}
+
+#[program]
+pub mod playtoearn_program {
+    use super::*;
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        payment_token_mint: Pubkey,
+        max_claim_per_user: u64,
+        total_supply_limit: u64,
+        backend_authority: Pubkey,
+        guardian: Pubkey,
+        mint_authority: Pubkey,
+    ) -> Result<()> {
+        #[cfg(not(feature = "structured-logs"))]
+        {
+            msg!("=== INITIALIZE CONFIG ===");
+            msg!("Payment Token Mint: {}", payment_token_mint);
+            msg!("Max Claim Per User: {}", max_claim_per_user);
+            msg!("Total Supply Limit: {}", total_supply_limit);
+        }
+
+        // Validar entrada
+        require!(payment_token_mint != Pubkey::default(), ErrorCode::InvalidInput);
+        require!(max_claim_per_user > 0, ErrorCode::InvalidInput);
+        require!(total_supply_limit > 0, ErrorCode::InvalidInput);
+        // Autoridades definidas já na criação, sem janela até os setters
+        require!(backend_authority != Pubkey::default(), ErrorCode::InvalidInput);
+        require!(guardian != Pubkey::default(), ErrorCode::InvalidInput);
+        require!(mint_authority != Pubkey::default(), ErrorCode::InvalidInput);
+        require_keys_eq!(
+            mint_authority,
+            ctx.accounts.mint_authority.key(),
+            ErrorCode::MintAuthorityMismatch
+        );
+
+        // Configurar a conta
+        let config = &mut ctx.accounts.config;
         config.admin = ctx.accounts.admin.key();
         config.payment_token_mint = payment_token_mint;
         config.emergency_paused = false;
         config.max_claim_per_user = max_claim_per_user;
         config.total_supply_limit = total_supply_limit;
         config.total_minted = 0;
+        config.allow_future_timestamps = false;
+        config.global_seq = 0;
+        config.config_bump = ctx.bumps.config;
+        config.mint_authority_bump = ctx.bumps.mint_authority;
+        config.claim_burn_bps = 0;
+        config.claim_fee_bps = 0;
+        config.enforce_mint_supply = true;
+        config.min_claim_amount = 1;
+        config.max_claim_amount = u64::MAX;
+        config.allowlist_enabled = false;
+        config.clawback_window_seconds = 0;
+        config.max_batch_size = DEFAULT_MAX_BATCH_SIZE;
+        config.pause_reason = String::new();
+        config.paused_at = 0;
+        config.pause_expires_at = 0;
+        config.instant_claim_threshold = 0;
+        config.escrow_release_delay = 0;
+        config.account_age_requirement_seconds = 0;
+        config.claims_in_current_minute = 0;
+        config.minute_window_start = 0;
+        config.claims_per_minute_threshold = 0;
+        // O guardian fica registrado, mas a troca emergencial de admin só é
+        // ativada por set_guardians com um threshold M-of-N: um guardian
+        // sozinho com threshold 1 poderia tomar o admin com uma única chave
+        config.guardians = vec![guardian];
+        config.guardian_threshold = 0;
+        config.treasury_bump = 0;
+        config.category_daily_caps = [0; MAX_CLAIM_CATEGORIES];
+        config.max_admin_mint_per_call = u64::MAX;
+        config.verbose_logging = true;
+        config.decimals = ctx.accounts.token_mint.decimals;
+        config.reject_same_slot_claims = false;
+        config.merkle_root = [0u8; 32];
+        config.max_claim_per_week = 0;
+        config.allow_admin_mint_while_paused = false;
+        config.mint_authority = mint_authority;
+        config.backend_authority = backend_authority;
+        config.emission = EmissionConfig {
+            initial_rate: BPS_DENOMINATOR,
+            halving_interval_seconds: 0,
+            genesis_ts: Clock::get()?.unix_timestamp,
+        };
+
+        // Limites são em unidades base: um supply que não é um número inteiro de
+        // tokens (ou menor que 1 token) quase sempre indica decimals esquecidos
+        let whole_tokens = config.from_base_units(total_supply_limit)?;
+        if whole_tokens == 0 || config.to_base_units(whole_tokens)? != total_supply_limit {
+            emit!(SecurityEvent {
+                event_type: "SUSPICIOUS_SUPPLY_LIMIT".to_string(),
+                user: config.admin,
+                reason: format!(
+                    "total_supply_limit {} is not a whole number of tokens with {} decimals",
+                    total_supply_limit, config.decimals
+                ),
+                timestamp: Clock::get()?.unix_timestamp,
+                global_seq: config.next_seq()?,
+            });
+        }
+
+        // A authority pode ser transferida depois, mas até lá os claims falham
+        if ctx.accounts.token_mint.mint_authority != COption::Some(config.mint_authority) {
+            emit!(SecurityEvent {
+                event_type: "MINT_AUTHORITY_MISMATCH".to_string(),
+                user: config.admin,
+                reason: format!(
+                    "Mint authority is not the program PDA {}; claims will fail until it is",
+                    config.mint_authority
+                ),
+                timestamp: Clock::get()?.unix_timestamp,
+                global_seq: config.next_seq()?,
+            });
+        }
 
-        msg!("✅ CONFIGURAÇÃO INICIALIZADA COM SUCESSO!");
-        msg!("Admin: {}", config.admin);
-        msg!("Payment Token: {}", config.payment_token_mint);
-        msg!("Max Claim Per User: {}", config.max_claim_per_user);
-        msg!("Total Supply Limit: {}", config.total_supply_limit);
+        #[cfg(not(feature = "structured-logs"))]
+        {
+            msg!("✅ CONFIGURAÇÃO INICIALIZADA COM SUCESSO!");
+            msg!("Admin: {}", config.admin);
+            msg!("Payment Token: {}", config.payment_token_mint);
+            msg!("Max Claim Per User: {}", config.max_claim_per_user);
+            msg!("Total Supply Limit: {}", config.total_supply_limit);
+        }
+        #[cfg(feature = "structured-logs")]
+        msg!(&structured_log_line(
+            "initialize_config",
+            &[
+                ("admin", &config.admin),
+                ("mint", &config.payment_token_mint),
+                ("max_claim", &config.max_claim_per_user),
+                ("supply_limit", &config.total_supply_limit),
+            ],
+        ));
 
         Ok(())
     }
@@ -181,36 +1928,75 @@ pub mod playtoearn_program {
         let blacklist = &mut ctx.accounts.blacklist;
         blacklist.admin = ctx.accounts.admin.key();
         blacklist.blacklisted_users = Vec::new();
+        blacklist.bump = ctx.bumps.blacklist;
 
         msg!("Blacklist inicializada com sucesso");
 
         Ok(())
     }
 
+    // Inicializar a conta de métricas do protocolo
+    pub fn initialize_stats(ctx: Context<InitializeStats>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let stats = &mut ctx.accounts.stats;
+        stats.unique_claimers = 0;
+        stats.lifetime_claims = 0;
+        stats.lifetime_burns = 0;
+        stats.lifetime_mints = 0;
+
+        msg!("Stats inicializadas com sucesso");
+
+        Ok(())
+    }
+
     pub fn burn_tokens(
         ctx: Context<BurnTokens>,
         amount: u64,
         timestamp: i64,
         signature: [u8; 64],
         description: String,
+        burn_reason_code: u8,
+        reference_id: u64,
     ) -> Result<()> {
-        msg!("=== BURN TOKENS WITH SIGNATURE ===");
-        msg!("Amount: {}", amount);
-        msg!("Description: {}", description);
+        #[cfg(feature = "profiling")]
+        let cu_start = anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
+
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("=== BURN TOKENS WITH SIGNATURE ===");
+            msg!("Amount: {}", amount);
+            msg!("Description: {}", description);
+            msg!("Reason Code: {}", burn_reason_code);
+        }
 
         require!(
-            !ctx.accounts.config.emergency_paused,
+            !ctx.accounts.config.is_paused(Clock::get()?.unix_timestamp),
             ErrorCode::SystemPaused
         );
         require!(amount > 0, ErrorCode::InvalidPaymentAmount);
-        require!(!description.is_empty(), ErrorCode::InvalidInput);
+        require!(amount <= MAX_REASONABLE_AMOUNT, ErrorCode::InvalidPaymentAmount);
+        // A descrição livre é opcional quando há um código de motivo estruturado
+        require!(
+            !description.is_empty() || burn_reason_code != BURN_REASON_UNSPECIFIED,
+            ErrorCode::InvalidInput
+        );
+        require!(description.len() <= MAX_DESC_LEN, ErrorCode::DescriptionTooLong);
 
-        // Recriar a mensagem original
-        let message = format!(
-            "{{\"wallet\":\"{}\",\"amount\":{},\"timestamp\":\"{}\",\"action\":\"burn\"}}",
-            ctx.accounts.payer.key(),
+        // Recriar a mensagem original (com o mint, o motivo e o nonce de queimas do usuário)
+        let burn_nonce = ctx.accounts.user_burn_account.burn_nonce;
+        let message = burn_message(
+            &ctx.accounts.payer.key(),
+            &ctx.accounts.payment_token_mint.key(),
             amount,
             timestamp,
+            burn_nonce,
+            burn_reason_code,
+            reference_id,
         );
         let message_bytes = message.as_bytes();
 
@@ -220,14 +2006,17 @@ pub mod playtoearn_program {
             message_bytes,
             &signature,
             &ctx.accounts.backend_authority.key(),
+            &ctx.accounts.payer.key(),
         )?;
 
         // Verificar se o tempo está dentro de um intervalo aceitável
         let now = Clock::get()?.unix_timestamp;
-        require!(
-            (now - timestamp).abs() <= 300, // 5 minutos de tolerância
-            ErrorCode::ExpiredSignature
-        );
+        validate_signature_timestamp(
+            now,
+            timestamp,
+            ctx.accounts.config.allow_future_timestamps,
+            &ctx.accounts.payer.key(),
+        )?;
 
         // Verificar saldo e queimar token
         require!(
@@ -235,6 +2024,19 @@ pub mod playtoearn_program {
             ErrorCode::InsufficientFunds
         );
 
+        // Checks-effects-interactions: o nonce e as estatísticas são gravados
+        // (serializados via exit, já que o Anchor só o faz no fim da instrução)
+        // antes do CPI, para que uma reentrada (ex. hooks do Token-2022) já
+        // encontre a assinatura consumida
+        let user_burn = &mut ctx.accounts.user_burn_account;
+        user_burn.user = ctx.accounts.payer.key();
+        user_burn.burn_nonce = burn_nonce.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+        if let Some(stats) = ctx.accounts.stats.as_mut() {
+            stats.record_burn()?;
+        }
+        ctx.accounts.exit(&crate::ID)?;
+
         let burn_ctx = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             Burn {
@@ -246,616 +2048,8149 @@ pub mod playtoearn_program {
 
         burn(burn_ctx, amount)?;
 
+        let global_seq = ctx.accounts.config.next_seq()?;
         emit!(TokenBurnEvent {
             payer: ctx.accounts.payer.key(),
             token_mint: ctx.accounts.payment_token_mint.key(),
             amount,
             description: description.clone(),
+            burn_reason_code,
+            reference_id,
             timestamp: now,
+            global_seq,
         });
 
-        msg!("🔥 TOKENS QUEIMADOS COM SUCESSO!");
-        msg!("Amount: {}", amount);
-        msg!("Description: {}", description);
-        msg!("User: {}", ctx.accounts.payer.key());
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("🔥 TOKENS QUEIMADOS COM SUCESSO!");
+            msg!("Amount: {}", amount);
+            msg!("Description: {}", description);
+            msg!("User: {}", ctx.accounts.payer.key());
+        }
+        #[cfg(feature = "structured-logs")]
+        msg!(&structured_log_line(
+            "burn",
+            &[("user", &ctx.accounts.payer.key()), ("amount", &amount), ("seq", &global_seq)],
+        ));
+
+        #[cfg(feature = "profiling")]
+        emit_compute_profile("burn", cu_start);
 
         Ok(())
     }
 
-    pub fn mint_tokens(
-        ctx: Context<MintTokens>,
+    // Queimar tokens de um usuário através de um delegate aprovado (SPL approve)
+    pub fn burn_from_delegate(
+        ctx: Context<BurnFromDelegate>,
         amount: u64,
-        recipient: Pubkey,
+        timestamp: i64,
+        signature: [u8; 64],
+        description: String,
     ) -> Result<()> {
-        msg!("=== MINT TOKENS ===");
-        msg!("Amount: {}", amount);
-        msg!("Recipient: {}", recipient);
-
-        // Verificar se o sistema não está pausado
-        require!(!ctx.accounts.config.emergency_paused, ErrorCode::SystemPaused);
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("=== BURN FROM DELEGATE ===");
+            msg!("Amount: {}", amount);
+            msg!("Owner: {}", ctx.accounts.owner.key());
+            msg!("Delegate: {}", ctx.accounts.delegate.key());
+        }
 
-        // Verificar se o chamador é o administrador
-        require_keys_eq!(
-            ctx.accounts.admin.key(),
-            ctx.accounts.config.admin,
-            ErrorCode::Unauthorized
+        require!(
+            !ctx.accounts.config.is_paused(Clock::get()?.unix_timestamp),
+            ErrorCode::SystemPaused
         );
-
-        // Verificar que a quantidade é válida
         require!(amount > 0, ErrorCode::InvalidPaymentAmount);
+        require!(amount <= MAX_REASONABLE_AMOUNT, ErrorCode::InvalidPaymentAmount);
+        require!(!description.is_empty(), ErrorCode::InvalidInput);
+        require!(description.len() <= MAX_DESC_LEN, ErrorCode::DescriptionTooLong);
 
-        // Verificar que o mint corresponde ao configurado
-        require_keys_eq!(
-            ctx.accounts.token_mint.key(),
-            ctx.accounts.config.payment_token_mint,
-            ErrorCode::InvalidPaymentToken
+        // Verificar a delegação na conta de token
+        let owner_token_account = &ctx.accounts.owner_token_account;
+        require!(
+            owner_token_account.delegate == COption::Some(ctx.accounts.delegate.key()),
+            ErrorCode::InvalidDelegate
+        );
+        require!(
+            owner_token_account.delegated_amount >= amount,
+            ErrorCode::InsufficientDelegatedAmount
         );
 
-        // Criar contexto para mintar tokens
-        let mint_to_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
-                mint: ctx.accounts.token_mint.to_account_info(),
-                to: ctx.accounts.recipient_token_account.to_account_info(),
-                authority: ctx.accounts.admin.to_account_info(),
-            },
+        // Recriar a mensagem original (inclui o delegate autorizado e o nonce de queimas do dono)
+        let burn_nonce = ctx.accounts.user_burn_account.burn_nonce;
+        let message = delegated_burn_message(
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.delegate.key(),
+            &ctx.accounts.payment_token_mint.key(),
+            amount,
+            timestamp,
+            burn_nonce,
         );
+        let message_bytes = message.as_bytes();
 
-        // Mintar os tokens
-        mint_to(mint_to_ctx, amount)?;
+        // Verificar assinatura
+        verify_signature(
+            &ctx.accounts.sysvar_instructions,
+            message_bytes,
+            &signature,
+            &ctx.accounts.backend_authority.key(),
+            &ctx.accounts.owner.key(),
+        )?;
 
-        // Emitir evento
         let now = Clock::get()?.unix_timestamp;
-        emit!(TokenMintEvent {
-            minter: ctx.accounts.admin.key(),
-            token_mint: ctx.accounts.token_mint.key(),
-            amount,
-            recipient,
-            timestamp: now,
-        });
-
-        msg!("🪙 TOKENS MINTADOS COM SUCESSO!");
-        msg!("Amount: {}", amount);
-        msg!("Recipient: {}", recipient);
-        msg!("Minter: {}", ctx.accounts.admin.key());
+        validate_signature_timestamp(
+            now,
+            timestamp,
+            ctx.accounts.config.allow_future_timestamps,
+            &ctx.accounts.owner.key(),
+        )?;
 
-        Ok(())
-    }
+        require!(
+            owner_token_account.amount >= amount,
+            ErrorCode::InsufficientFunds
+        );
 
-    pub fn claim_tokens(
-        ctx: Context<ClaimTokens>,
-        amount: u64,
-        timestamp: i64,
-        signature: [u8; 64],
-    ) -> Result<()> {
-        msg!("=== CLAIM TOKENS ===");
-        msg!("Amount: {}", amount);
-        msg!("User: {}", ctx.accounts.claimer.key());
+        // Checks-effects-interactions: o nonce e as estatísticas são gravados
+        // (serializados via exit, já que o Anchor só o faz no fim da instrução)
+        // antes do CPI, para que uma reentrada (ex. hooks do Token-2022) já
+        // encontre a assinatura consumida
+        let user_burn = &mut ctx.accounts.user_burn_account;
+        user_burn.user = ctx.accounts.owner.key();
+        user_burn.burn_nonce = burn_nonce.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
 
-        require!(!ctx.accounts.config.emergency_paused, ErrorCode::SystemPaused);
-        require!(amount > 0, ErrorCode::InvalidPaymentAmount);
+        if let Some(stats) = ctx.accounts.stats.as_mut() {
+            stats.record_burn()?;
+        }
+        ctx.accounts.exit(&crate::ID)?;
 
-        // Verificar se usuário não está na blacklist
-        require!(!ctx.accounts.user_claim_account.is_blacklisted, ErrorCode::Unauthorized);
+        let burn_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.payment_token_mint.to_account_info(),
+                from: ctx.accounts.owner_token_account.to_account_info(),
+                authority: ctx.accounts.delegate.to_account_info(),
+            },
+        );
 
-        // Verificar limites de supply total
-        let new_total = ctx.accounts.config.total_minted.checked_add(amount)
-            .ok_or(ErrorCode::MathOverflow)?;
-        require!(new_total <= ctx.accounts.config.total_supply_limit, ErrorCode::InvalidPaymentAmount);
+        burn(burn_ctx, amount)?;
 
-        // Verificar assinatura do backend
-        let message = format!(
-            "{{\"wallet\":\"{}\",\"amount\":{},\"timestamp\":\"{}\",\"action\":\"claim\"}}",
-            ctx.accounts.claimer.key(),
+        let global_seq = ctx.accounts.config.next_seq()?;
+        emit!(TokenBurnEvent {
+            payer: ctx.accounts.owner.key(),
+            token_mint: ctx.accounts.payment_token_mint.key(),
             amount,
-            timestamp,
-        );
-        let message_bytes = message.as_bytes();
-
-        verify_signature(
-            &ctx.accounts.sysvar_instructions,
-            message_bytes,
-            &signature,
-            &ctx.accounts.backend_authority.key(),
-        )?;
+            description: description.clone(),
+            burn_reason_code: BURN_REASON_UNSPECIFIED,
+            reference_id: 0,
+            timestamp: now,
+            global_seq,
+        });
 
-        // Verificar timestamp (5 minutos de tolerância)
-        let now = Clock::get()?.unix_timestamp;
-        require!(
-            (now - timestamp).abs() <= 300,
-            ErrorCode::ExpiredSignature
-        );
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("🔥 TOKENS QUEIMADOS VIA DELEGATE COM SUCESSO!");
+            msg!("Amount: {}", amount);
+            msg!("Description: {}", description);
+            msg!("Owner: {}", ctx.accounts.owner.key());
+        }
+        #[cfg(feature = "structured-logs")]
+        msg!(&structured_log_line(
+            "burn_from_delegate",
+            &[
+                ("user", &ctx.accounts.owner.key()),
+                ("delegate", &ctx.accounts.delegate.key()),
+                ("amount", &amount),
+                ("seq", &global_seq),
+            ],
+        ));
 
-        // Verificar limites por usuário
-        let user_claim = &mut ctx.accounts.user_claim_account;
-        let one_day_seconds: i64 = 24 * 60 * 60;
+        Ok(())
+    }
 
-        // Inicializar conta se for nova
-        if ctx.accounts.user_claim_account.to_account_info().data_is_empty() {
-            user_claim.user = ctx.accounts.claimer.key();
-            user_claim.total_claimed = 0;
-            user_claim.last_claim_timestamp = 0;
-            user_claim.daily_claimed = 0;
-            user_claim.daily_reset_timestamp = now;
-            user_claim.hourly_claimed = 0;
-            user_claim.hourly_reset_timestamp = now;
-            user_claim.nonce = 0;
-            user_claim.is_blacklisted = false;
+    pub fn mint_tokens(
+        ctx: Context<MintTokens>,
+        amount: u64,
+        recipient: Pubkey,
+    ) -> Result<()> {
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("=== MINT TOKENS ===");
+            msg!("Amount: {}", amount);
+            msg!("Recipient: {}", recipient);
         }
 
-        // Resetar contadores se necessário
-        if now - user_claim.daily_reset_timestamp >= one_day_seconds {
-            user_claim.daily_claimed = 0;
-            user_claim.daily_reset_timestamp = now;
+        // Verificar se o sistema não está pausado (ou se o mint de recuperação é permitido)
+        if ctx.accounts.config.is_paused(Clock::get()?.unix_timestamp) {
+            require!(
+                ctx.accounts.config.allow_admin_mint_while_paused,
+                ErrorCode::SystemPaused
+            );
+            emit!(SecurityEvent {
+                event_type: "PAUSED_ADMIN_MINT".to_string(),
+                user: recipient,
+                reason: format!("Admin mint of {} during emergency pause", amount),
+                timestamp: Clock::get()?.unix_timestamp,
+                global_seq: ctx.accounts.config.next_seq()?,
+            });
         }
 
-        let one_hour_seconds: i64 = 60 * 60;
-        if now - user_claim.hourly_reset_timestamp >= one_hour_seconds {
-            user_claim.hourly_claimed = 0;
-            user_claim.hourly_reset_timestamp = now;
-        }
+        // Verificar se o chamador é o administrador
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
 
-        // Verificar limites
-        let max_hourly = ctx.accounts.config.max_claim_per_user / 24; // Máximo por hora (1/24 do diário)
-        let new_hourly_total = user_claim.hourly_claimed.checked_add(amount)
-            .ok_or(ErrorCode::MathOverflow)?;
-        require!(new_hourly_total <= max_hourly, ErrorCode::InvalidPaymentAmount);
+        // Verificar que a quantidade é válida
+        require!(amount > 0, ErrorCode::InvalidPaymentAmount);
+        require!(amount <= MAX_REASONABLE_AMOUNT, ErrorCode::InvalidPaymentAmount);
+        require!(
+            amount <= ctx.accounts.config.max_admin_mint_per_call,
+            ErrorCode::AdminMintTooLarge
+        );
 
-        let new_daily_total = user_claim.daily_claimed.checked_add(amount)
-            .ok_or(ErrorCode::MathOverflow)?;
-        require!(new_daily_total <= ctx.accounts.config.max_claim_per_user, ErrorCode::InvalidPaymentAmount);
+        // Rejeitar antes do CPI um mint que estouraria o u64 do SPL Token
+        ctx.accounts.token_mint.supply.checked_add(amount)
+            .ok_or(ErrorCode::MintSupplyOverflow)?;
 
-        // Atualizar dados do usuário
-        user_claim.total_claimed = user_claim.total_claimed.checked_add(amount)
-            .ok_or(ErrorCode::MathOverflow)?;
-        user_claim.daily_claimed = new_daily_total;
-        user_claim.hourly_claimed = new_hourly_total;
-        user_claim.last_claim_timestamp = now;
-        user_claim.nonce = user_claim.nonce.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        // O destinatário informado deve ser o mesmo da conta passada
+        require_keys_eq!(
+            recipient,
+            ctx.accounts.recipient.key(),
+            ErrorCode::InvalidTokenAccount
+        );
 
-        // Atualizar total mintado global
-        let config = &mut ctx.accounts.config;
-        config.total_minted = new_total;
+        // Mints administrativos contam no mesmo limite de supply dos claims
+        // (token registrado ou token principal); gravado antes do CPI
+        match ctx.accounts.registered_token.as_mut() {
+            Some(registered) => {
+                let new_total = registered.total_minted.checked_add(amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                require!(new_total <= registered.supply_limit, ErrorCode::InvalidPaymentAmount);
+                registered.total_minted = new_total;
+            }
+            None => {
+                let config = &mut ctx.accounts.config;
+                let new_total = config.total_minted.checked_add(amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                require!(new_total <= config.total_supply_limit, ErrorCode::InvalidPaymentAmount);
+                config.total_minted = new_total;
+            }
+        }
 
-        // Mintar tokens
-        let mint_to_ctx = CpiContext::new(
+        // Criar contexto para mintar tokens (o PDA assina com o bump salvo na config)
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[b"mint_authority", &[ctx.accounts.config.mint_authority_bump]]];
+        let mint_to_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             MintTo {
                 mint: ctx.accounts.token_mint.to_account_info(),
-                to: ctx.accounts.claimer_token_account.to_account_info(),
+                to: ctx.accounts.recipient_token_account.to_account_info(),
                 authority: ctx.accounts.mint_authority.to_account_info(),
             },
+            signer_seeds,
         );
 
+        // Mintar os tokens
         mint_to(mint_to_ctx, amount)?;
 
+        if let Some(stats) = ctx.accounts.stats.as_mut() {
+            stats.record_mint()?;
+        }
+
         // Emitir evento
-        emit!(TokenClaimEvent {
-            claimer: ctx.accounts.claimer.key(),
+        let now = Clock::get()?.unix_timestamp;
+        let global_seq = ctx.accounts.config.next_seq()?;
+        emit!(TokenMintEvent {
+            minter: ctx.accounts.admin.key(),
             token_mint: ctx.accounts.token_mint.key(),
             amount,
+            recipient,
             timestamp: now,
+            global_seq,
         });
 
-        msg!("🎁 TOKENS CLAIMADOS COM SUCESSO!");
-        msg!("Amount: {}", amount);
-        msg!("User: {}", ctx.accounts.claimer.key());
-        msg!("New Total Supply: {}", config.total_minted);
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("🪙 TOKENS MINTADOS COM SUCESSO!");
+            msg!("Amount: {}", amount);
+            msg!("Recipient: {}", recipient);
+            msg!("Minter: {}", ctx.accounts.admin.key());
+        }
+        #[cfg(feature = "structured-logs")]
+        msg!(&structured_log_line(
+            "mint",
+            &[
+                ("minter", &ctx.accounts.admin.key()),
+                ("recipient", &recipient),
+                ("amount", &amount),
+                ("seq", &global_seq),
+            ],
+        ));
 
         Ok(())
     }
 
-    // Gerenciamento da blacklist
-    pub fn add_to_blacklist(ctx: Context<ManageBlacklist>, user: Pubkey) -> Result<()> {
+    // Mintar para vários destinatários em uma transação. As ATAs dos
+    // destinatários são passadas em remaining_accounts, na mesma ordem.
+    pub fn batch_mint_tokens<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchMintTokens<'info>>,
+        recipients: Vec<Pubkey>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("=== BATCH MINT TOKENS ===");
+            msg!("Recipients: {}", recipients.len());
+        }
+
+        if ctx.accounts.config.is_paused(Clock::get()?.unix_timestamp) {
+            require!(
+                ctx.accounts.config.allow_admin_mint_while_paused,
+                ErrorCode::SystemPaused
+            );
+            emit!(SecurityEvent {
+                event_type: "PAUSED_ADMIN_MINT".to_string(),
+                user: ctx.accounts.admin.key(),
+                reason: format!(
+                    "Admin batch mint to {} recipients during emergency pause",
+                    recipients.len()
+                ),
+                timestamp: Clock::get()?.unix_timestamp,
+                global_seq: ctx.accounts.config.next_seq()?,
+            });
+        }
         require_keys_eq!(
             ctx.accounts.admin.key(),
             ctx.accounts.config.admin,
             ErrorCode::Unauthorized
         );
+        require!(!recipients.is_empty(), ErrorCode::InvalidInput);
+        require!(
+            recipients.len() <= ctx.accounts.config.max_batch_size as usize,
+            ErrorCode::BatchTooLarge
+        );
+        require!(recipients.len() == amounts.len(), ErrorCode::InvalidInput);
+        require!(
+            ctx.remaining_accounts.len() == recipients.len(),
+            ErrorCode::InvalidInput
+        );
 
-        let blacklist = &mut ctx.accounts.blacklist;
-        if !blacklist.blacklisted_users.contains(&user) {
-            blacklist.blacklisted_users.push(user);
+        // Rejeitar antes dos CPIs um lote que estouraria o u64 do SPL Token
+        amounts.iter().try_fold(ctx.accounts.token_mint.supply, |supply, amount| {
+            supply.checked_add(*amount)
+        }).ok_or(ErrorCode::MintSupplyOverflow)?;
+
+        // O lote inteiro conta no limite de supply, gravado antes dos CPIs
+        let config = &mut ctx.accounts.config;
+        let new_total = amounts.iter()
+            .try_fold(config.total_minted, |total, amount| total.checked_add(*amount))
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(new_total <= config.total_supply_limit, ErrorCode::InvalidPaymentAmount);
+        config.total_minted = new_total;
 
-            // Marcar na conta do usuário também
-            if !ctx.accounts.user_claim_account.to_account_info().data_is_empty() {
-                ctx.accounts.user_claim_account.is_blacklisted = true;
+        let token_mint = ctx.accounts.token_mint.key();
+        let now = Clock::get()?.unix_timestamp;
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[b"mint_authority", &[ctx.accounts.config.mint_authority_bump]]];
+
+        for ((recipient, amount), recipient_token_account) in recipients
+            .iter()
+            .zip(amounts.iter())
+            .zip(ctx.remaining_accounts.iter())
+        {
+            require!(*amount > 0, ErrorCode::InvalidPaymentAmount);
+            require!(*amount <= MAX_REASONABLE_AMOUNT, ErrorCode::InvalidPaymentAmount);
+            require!(
+                *amount <= ctx.accounts.config.max_admin_mint_per_call,
+                ErrorCode::AdminMintTooLarge
+            );
+
+            // A conta deve ser a ATA canônica do destinatário
+            require_keys_eq!(
+                recipient_token_account.key(),
+                get_associated_token_address_with_program_id(
+                    recipient,
+                    &token_mint,
+                    &ctx.accounts.token_program.key(),
+                ),
+                ErrorCode::InvalidTokenAccount
+            );
+
+            let mint_to_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: recipient_token_account.clone(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            mint_to(mint_to_ctx, *amount)?;
+
+            if let Some(stats) = ctx.accounts.stats.as_mut() {
+                stats.record_mint()?;
             }
 
-            emit!(SecurityEvent {
-                event_type: "USER_BLACKLISTED".to_string(),
-                user,
-                reason: "Added to blacklist by admin".to_string(),
-                timestamp: Clock::get()?.unix_timestamp,
+            let global_seq = ctx.accounts.config.next_seq()?;
+            emit!(TokenMintEvent {
+                minter: ctx.accounts.admin.key(),
+                token_mint,
+                amount: *amount,
+                recipient: *recipient,
+                timestamp: now,
+                global_seq,
             });
+        }
 
-            emit!(AdminActionEvent {
-                admin: ctx.accounts.admin.key(),
-                action: "BLACKLIST_ADD".to_string(),
-                details: format!("User {} added to blacklist", user),
-                timestamp: Clock::get()?.unix_timestamp,
-            });
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("🪙 BATCH MINT CONCLUÍDO COM SUCESSO!");
         }
+        #[cfg(feature = "structured-logs")]
+        msg!(&structured_log_line(
+            "batch_mint",
+            &[("minter", &ctx.accounts.admin.key()), ("recipients", &recipients.len())],
+        ));
 
         Ok(())
     }
 
-    pub fn remove_from_blacklist(ctx: Context<ManageBlacklist>, user: Pubkey) -> Result<()> {
-        require_keys_eq!(
-            ctx.accounts.admin.key(),
-            ctx.accounts.config.admin,
-            ErrorCode::Unauthorized
-        );
+    pub fn claim_tokens(ctx: Context<ClaimTokens>, args: ClaimArgs) -> Result<()> {
+        process_claim(ctx, args, None, None)
+    }
 
-        let blacklist = &mut ctx.accounts.blacklist;
-        if let Some(index) = blacklist.blacklisted_users.iter().position(|&x| x == user) {
-            blacklist.blacklisted_users.remove(index);
+    // Claim para carteiras da allowlist merkle: a prova do pubkey do claimer
+    // contra config.merkle_root substitui a AllowlistEntry
+    pub fn claim_tokens_merkle(
+        ctx: Context<ClaimTokens>,
+        args: ClaimArgs,
+        merkle_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        process_claim(ctx, args, Some(&merkle_proof), None)
+    }
 
-            // Desmarcar na conta do usuário
-            if !ctx.accounts.user_claim_account.to_account_info().data_is_empty() {
-                ctx.accounts.user_claim_account.is_blacklisted = false;
-            }
+    // Claim autorizado por lote: uma única instrução ED25519 assina a raiz
+    // merkle das mensagens de vários claims, e cada claim prova que sua
+    // mensagem (mesmo formato de claim_tokens) é uma folha dessa raiz
+    pub fn claim_tokens_batched(
+        ctx: Context<ClaimTokens>,
+        args: ClaimArgs,
+        batch: ClaimBatchProof,
+    ) -> Result<()> {
+        process_claim(ctx, args, None, Some(&batch))
+    }
 
-            emit!(SecurityEvent {
-                event_type: "USER_UNBLACKLISTED".to_string(),
-                user,
-                reason: "Removed from blacklist by admin".to_string(),
-                timestamp: Clock::get()?.unix_timestamp,
-            });
-        }
+    // Fechar o registro de uma sessão de gameplay depois da retenção,
+    // devolvendo o rent ao claimer que o pagou
+    pub fn close_claimed_session(ctx: Context<CloseClaimedSession>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(ctx.accounts.claimed_session.closable(now), ErrorCode::InvalidInput);
+        Ok(())
+    }
+
+    // Liberar o saldo do escrow de vesting depois de unlock_at. Os tokens já
+    // contam em total_minted desde o claim; aqui são efetivamente mintados
+    pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(!ctx.accounts.config.is_paused(now), ErrorCode::SystemPaused);
+
+        let amount = ctx.accounts.escrow.amount;
+        require!(amount > 0, ErrorCode::InvalidPaymentAmount);
+        require!(now >= ctx.accounts.escrow.unlock_at, ErrorCode::EscrowLocked);
+        ctx.accounts.token_mint.supply
+            .checked_add(amount)
+            .ok_or(ErrorCode::MintSupplyOverflow)?;
+
+        // Zerar o saldo antes do CPI
+        ctx.accounts.escrow.amount = 0;
+
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[b"mint_authority", &[ctx.accounts.config.mint_authority_bump]]];
+        let mint_to_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.mint_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        mint_to(mint_to_ctx, amount)?;
+
+        emit!(EscrowEvent {
+            action: "RELEASE".to_string(),
+            user: ctx.accounts.user.key(),
+            amount,
+            unlock_at: ctx.accounts.escrow.unlock_at,
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
 
         Ok(())
     }
 
-    // Solicitar mudança administrativa (com delay de 24h)
-    pub fn request_admin_action(
-        ctx: Context<RequestAdminAction>,
-        action_type: AdminActionType,
-        new_value: Pubkey,
+    // Simular um claim sem alterar estado, emitindo o primeiro check que
+    // falharia. A assinatura do backend não é verificada.
+    pub fn simulate_claim(
+        ctx: Context<SimulateClaim>,
+        amount: u64,
+        timestamp: i64,
+        category: u8,
     ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let user = ctx.accounts.claimer.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        // Categoria inválida é reportada como check falho, não como erro
+        let limits = category_claim_limits(
+            config,
+            config.max_claim_per_user,
+            ctx.accounts.claim_role.as_deref(),
+            ctx.accounts.user_claim_account.as_ref().and_then(|u| u.custom_daily_limit),
+            category,
+            now,
+        );
+        let valid_category = limits.is_ok();
+        let (max_daily, max_hourly) = limits.unwrap_or((0, 0));
+
+        // Mesma janela deslizante usada por claim_tokens
+        let (daily_claimed, hourly_claimed, recent_claims, flagged) =
+            match &ctx.accounts.user_claim_account {
+                Some(user_claim) => (
+                    user_claim.claimed_within(now, ONE_DAY_SECONDS, category)?,
+                    user_claim.claimed_within(now, ONE_HOUR_SECONDS, category)?,
+                    user_claim
+                        .claim_history
+                        .iter()
+                        .filter(|entry| now - entry.timestamp < ONE_DAY_SECONDS)
+                        .count(),
+                    user_claim.is_blacklisted,
+                ),
+                None => (0, 0, 0, false),
+            };
+        let daily_remaining = max_daily.saturating_sub(daily_claimed);
+        let hourly_remaining = max_hourly.saturating_sub(hourly_claimed);
+        let weekly_remaining = match (&ctx.accounts.user_claim_account, config.max_claim_per_week) {
+            (_, 0) => u64::MAX,
+            (Some(user_claim), cap) => cap.saturating_sub(user_claim.claimed_this_week(now)),
+            (None, cap) => cap,
+        };
+        let supply_remaining = config.total_supply_limit.saturating_sub(config.total_minted);
+
+        let (_, net_amount) = split_bps(amount, config.claim_burn_bps as u64)?;
+        let fee_amount = if ctx.accounts.claim_role.as_ref().is_some_and(|role| role.fee_exempt) {
+            0
+        } else {
+            apply_bps(amount, config.claim_fee_bps as u64)?
+        };
+
+        let failing_check = if config.is_paused(now) {
+            "PAUSED"
+        } else if amount == 0 {
+            "INVALID_AMOUNT"
+        } else if amount < config.min_claim_amount || amount > config.max_claim_amount {
+            "AMOUNT_BOUNDS"
+        } else if flagged
+            || blacklist_contains(&ctx.accounts.blacklist, &user)?
+            || blacklist_entry_active(&ctx.accounts.blacklist_entry, now)?
+        {
+            "BLACKLISTED"
+        } else if ctx.accounts.user_claim_account.as_ref()
+            .is_some_and(|user_claim| now < user_claim.suspended_until)
+        {
+            "SUSPENDED"
+        } else if config.allowlist_enabled && ctx.accounts.allowlist_entry.is_none() {
+            "NOT_ALLOWLISTED"
+        } else if config.account_age_requirement_seconds > 0
+            && !matches!(
+                &ctx.accounts.user_registration,
+                Some(registration)
+                    if now - registration.registered_at >= config.account_age_requirement_seconds
+            )
+        {
+            "ACCOUNT_TOO_YOUNG"
+        } else if timestamp > now && !config.allow_future_timestamps {
+            "SIGNATURE_FROM_FUTURE"
+        } else if (now - timestamp).abs() > SIGNATURE_TOLERANCE_SECONDS {
+            "EXPIRED_SIGNATURE"
+        } else if config.circuit_breaker_tripped(now) {
+            "CIRCUIT_BREAKER"
+        } else if net_amount.saturating_add(fee_amount) > supply_remaining {
+            "SUPPLY_LIMIT"
+        } else if config.enforce_mint_supply
+            && ctx.accounts.token_mint.supply.saturating_add(amount) > config.total_supply_limit
+        {
+            "MINT_SUPPLY_LIMIT"
+        } else if !valid_category {
+            "INVALID_CATEGORY"
+        } else if recent_claims >= MAX_CLAIM_HISTORY {
+            "CLAIM_HISTORY_FULL"
+        } else if amount > hourly_remaining {
+            "HOURLY_LIMIT"
+        } else if amount > daily_remaining {
+            "DAILY_LIMIT"
+        } else if amount > weekly_remaining {
+            "WEEKLY_LIMIT"
+        } else {
+            "NONE"
+        };
+
+        emit!(ClaimSimulationEvent {
+            user,
+            amount,
+            would_succeed: failing_check == "NONE",
+            failing_check: failing_check.to_string(),
+            daily_remaining,
+            hourly_remaining,
+            supply_remaining,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Definir (ou remover, com None) o limite diário absoluto de um usuário
+    pub fn set_user_limit(ctx: Context<SetUserLimit>, custom_daily_limit: Option<u64>) -> Result<()> {
         require_keys_eq!(
             ctx.accounts.admin.key(),
             ctx.accounts.config.admin,
             ErrorCode::Unauthorized
         );
 
-        let pending_action = &mut ctx.accounts.pending_action;
-        pending_action.action_type = action_type.clone();
-        pending_action.new_value = new_value;
-        pending_action.requested_at = Clock::get()?.unix_timestamp;
-        pending_action.executed = false;
+        let user = ctx.accounts.user.key();
+
+        // Limites muito acima do padrão precisam de uma ação SetUserLimit
+        // solicitada há 24h para este usuário e este valor
+        let default_limit = match &ctx.accounts.registered_token {
+            Some(registered) => registered.max_claim_per_user,
+            None => ctx.accounts.config.max_claim_per_user,
+        };
+        if let Some(limit) = custom_daily_limit {
+            if limit > default_limit.saturating_mul(LARGE_USER_LIMIT_MULTIPLIER) {
+                let pending_action = ctx.accounts.pending_action.as_mut()
+                    .ok_or(ErrorCode::InvalidInput)?;
+                require!(
+                    matches!(pending_action.action_type, AdminActionType::SetUserLimit)
+                        && pending_action.new_value == user
+                        && pending_action.new_amount == limit,
+                    ErrorCode::InvalidInput
+                );
+                require!(!pending_action.executed, ErrorCode::InvalidInput);
+                require!(
+                    Clock::get()?.unix_timestamp - pending_action.requested_at
+                        >= ADMIN_ACTION_DELAY_SECONDS,
+                    ErrorCode::InvalidInput
+                );
+                pending_action.close(ctx.accounts.admin.to_account_info())?;
+            }
+        }
+
+        let user_claim = &mut ctx.accounts.user_claim_account;
+        user_claim.user = user;
+        user_claim.bump = ctx.bumps.user_claim_account;
+        user_claim.custom_daily_limit = custom_daily_limit;
 
         emit!(AdminActionEvent {
             admin: ctx.accounts.admin.key(),
-            action: format!("REQUEST_{:?}", action_type),
-            details: format!("Requested change to {}", new_value),
+            action: "SET_USER_LIMIT".to_string(),
+            details: format!("custom_daily_limit for {} set to {:?}", user, custom_daily_limit),
             timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
         });
 
-        msg!("Admin action requested. Execute after 24h delay for security.");
-
         Ok(())
     }
 
-    // Executar mudança administrativa após delay
-    pub fn execute_admin_action(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+    // Suspender os claims de um usuário por `duration` segundos enquanto o caso
+    // é revisado, sem os efeitos da blacklist. duration = 0 encerra a suspensão
+    pub fn suspend_user(ctx: Context<SuspendUser>, duration: i64) -> Result<()> {
         require_keys_eq!(
             ctx.accounts.admin.key(),
             ctx.accounts.config.admin,
             ErrorCode::Unauthorized
         );
-
-        let pending_action = &ctx.accounts.pending_action;
-        require!(!pending_action.executed, ErrorCode::InvalidInput);
+        require!(duration >= 0, ErrorCode::InvalidInput);
 
         let now = Clock::get()?.unix_timestamp;
-        let delay_seconds: i64 = 24 * 60 * 60; // 24 horas
-        require!(
-            now - pending_action.requested_at >= delay_seconds,
-            ErrorCode::InvalidInput
-        );
-
-        let config = &mut ctx.accounts.config;
+        let suspended_until = if duration == 0 {
+            0
+        } else {
+            now.checked_add(duration).ok_or(ErrorCode::MathOverflow)?
+        };
 
-        match pending_action.action_type {
-            AdminActionType::ChangeAdmin => {
-                config.admin = pending_action.new_value;
-                emit!(AdminActionEvent {
-                    admin: ctx.accounts.admin.key(),
-                    action: "CHANGE_ADMIN".to_string(),
-                    details: format!("Admin changed to {}", pending_action.new_value),
-                    timestamp: now,
-                });
-            },
-            AdminActionType::ChangeToken => {
-                config.payment_token_mint = pending_action.new_value;
-                emit!(AdminActionEvent {
-                    admin: ctx.accounts.admin.key(),
-                    action: "CHANGE_TOKEN".to_string(),
-                    details: format!("Token changed to {}", pending_action.new_value),
-                    timestamp: now,
-                });
-            },
-            AdminActionType::EmergencyWithdraw => {
-                // Emergency withdraw logic would go here
-                emit!(AdminActionEvent {
-                    admin: ctx.accounts.admin.key(),
-                    action: "EMERGENCY_WITHDRAW".to_string(),
-                    details: "Emergency withdraw executed".to_string(),
-                    timestamp: now,
-                });
-            },
-        }
+        let user = ctx.accounts.user.key();
+        let user_claim = &mut ctx.accounts.user_claim_account;
+        user_claim.user = user;
+        user_claim.bump = ctx.bumps.user_claim_account;
+        user_claim.suspended_until = suspended_until;
 
-        // Marcar como executado
-        ctx.accounts.pending_action.executed = true;
+        emit!(SecurityEvent {
+            event_type: if duration == 0 { "USER_UNSUSPENDED" } else { "USER_SUSPENDED" }.to_string(),
+            user,
+            reason: format!("Claims suspended until {}", suspended_until),
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
 
         Ok(())
     }
 
-    // Circuit breaker - pausa automática se detectar atividade suspeita
-    pub fn emergency_pause(ctx: Context<EmergencyPause>, reason: String) -> Result<()> {
+    // Zerar os contadores diário/horário de um usuário numa categoria (suporte,
+    // ex. após contagem dupla por bug do cliente). A janela semanal e as outras
+    // categorias são mantidas. Usuários banidos não podem ser resetados.
+    pub fn reset_user_limits(ctx: Context<ResetUserLimits>, category: u8) -> Result<()> {
         require_keys_eq!(
             ctx.accounts.admin.key(),
             ctx.accounts.config.admin,
             ErrorCode::Unauthorized
         );
 
-        ctx.accounts.config.emergency_paused = true;
+        let user = ctx.accounts.user.key();
+        require!(!ctx.accounts.user_claim_account.is_blacklisted, ErrorCode::Unauthorized);
+        require!(
+            !ctx.accounts.blacklist.blacklisted_users.contains(&user),
+            ErrorCode::Unauthorized
+        );
+        require!((category as usize) < MAX_CLAIM_CATEGORIES, ErrorCode::InvalidClaimCategory);
 
-        emit!(SecurityEvent {
-            event_type: "EMERGENCY_PAUSE".to_string(),
-            user: ctx.accounts.admin.key(),
-            reason,
-            timestamp: Clock::get()?.unix_timestamp,
+        let now = Clock::get()?.unix_timestamp;
+        let user_claim = &mut ctx.accounts.user_claim_account;
+        let previous_daily = user_claim.claimed_within(now, ONE_DAY_SECONDS, category)?;
+        let previous_hourly = user_claim.claimed_within(now, ONE_HOUR_SECONDS, category)?;
+        user_claim.reset_category_window(category);
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "RESET_USER_LIMITS".to_string(),
+            details: format!(
+                "Limits reset for {} in category {} (daily {} -> 0, hourly {} -> 0)",
+                user, category, previous_daily, previous_hourly
+            ),
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
         });
 
         Ok(())
     }
-}
-
-#[derive(Accounts)]
-pub struct ClaimTokens<'info> {
-    #[account(mut)]
-    pub claimer: Signer<'info>,
 
+    // Ressincronizar o nonce de claims de um usuário com o backend (ex. payload
+    // assinado parcialmente processado). O nonce só avança, para que assinaturas
+    // antigas não voltem a ser válidas; saltos grandes exigem timelock.
+    pub fn reset_user_nonce(ctx: Context<ResetUserNonce>, new_nonce: u64) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let user = ctx.accounts.user.key();
+        let previous_nonce = ctx.accounts.user_claim_account.claim_nonce;
+        require!(new_nonce > previous_nonce, ErrorCode::InvalidInput);
+
+        if new_nonce - previous_nonce > MAX_NONCE_RESET_JUMP {
+            let pending_action = ctx.accounts.pending_action.as_mut()
+                .ok_or(ErrorCode::InvalidInput)?;
+            require!(
+                matches!(pending_action.action_type, AdminActionType::ResetUserNonce)
+                    && pending_action.new_value == user
+                    && pending_action.new_amount == new_nonce,
+                ErrorCode::InvalidInput
+            );
+            require!(!pending_action.executed, ErrorCode::InvalidInput);
+            require!(
+                Clock::get()?.unix_timestamp - pending_action.requested_at
+                    >= ADMIN_ACTION_DELAY_SECONDS,
+                ErrorCode::InvalidInput
+            );
+            pending_action.close(ctx.accounts.admin.to_account_info())?;
+        }
+
+        ctx.accounts.user_claim_account.claim_nonce = new_nonce;
+
+        emit!(SecurityEvent {
+            event_type: "USER_NONCE_RESET".to_string(),
+            user,
+            reason: format!("claim_nonce {} -> {}", previous_nonce, new_nonce),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Gerenciamento da blacklist
+    // expires_at = 0 bane permanentemente (lista e flag do usuário); um
+    // timestamp futuro cria ou estende a BlacklistEntry do usuário, que deixa
+    // de valer sozinha e é fechada depois por prune_blacklist_entry
+    pub fn add_to_blacklist(
+        ctx: Context<AddToBlacklist>,
+        user: Pubkey,
+        expires_at: i64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        // O PDA de claim é derivado da conta `user`; o argumento deve coincidir
+        require_keys_eq!(user, ctx.accounts.user.key(), ErrorCode::InvalidInput);
+        let now = Clock::get()?.unix_timestamp;
+        require!(expires_at == 0 || expires_at > now, ErrorCode::InvalidInput);
+
+        if expires_at > 0 {
+            let bump = ctx.bumps.blacklist_entry.ok_or(ErrorCode::InvalidInput)?;
+            let entry = ctx.accounts.blacklist_entry.as_mut()
+                .ok_or(ErrorCode::InvalidInput)?;
+            entry.user = user;
+            entry.expires_at = expires_at;
+            entry.created_at = now;
+            entry.bump = bump;
+
+            emit!(SecurityEvent {
+                event_type: "USER_BLACKLISTED_TEMPORARY".to_string(),
+                user,
+                reason: format!("Temporary ban by admin until {}", expires_at),
+                timestamp: now,
+                global_seq: ctx.accounts.config.next_seq()?,
+            });
+            return Ok(());
+        }
+        // Uma entrada criada aqui ficaria sem prazo nem bump
+        require!(ctx.accounts.blacklist_entry.is_none(), ErrorCode::InvalidInput);
+
+        // Conta recém-criada: registrar o dono e o bump (claim_tokens completa o resto)
+        let user_claim = &mut ctx.accounts.user_claim_account;
+        if user_claim.user == Pubkey::default() {
+            user_claim.user = user;
+            user_claim.bump = ctx.bumps.user_claim_account;
+        }
+        // Marcar na conta do usuário também (mesmo se já estava na lista)
+        user_claim.is_blacklisted = true;
+
+        let blacklist = &mut ctx.accounts.blacklist;
+        if !blacklist.blacklisted_users.contains(&user) {
+            // A conta foi alocada para MAX_BLACKLIST_SIZE usuários
+            require!(
+                blacklist.blacklisted_users.len() < MAX_BLACKLIST_SIZE,
+                ErrorCode::BlacklistFull
+            );
+            blacklist.blacklisted_users.push(user);
+
+            emit!(SecurityEvent {
+                event_type: "USER_BLACKLISTED".to_string(),
+                user,
+                reason: "Added to blacklist by admin".to_string(),
+                timestamp: Clock::get()?.unix_timestamp,
+                global_seq: ctx.accounts.config.next_seq()?,
+            });
+
+            emit!(AdminActionEvent {
+                admin: ctx.accounts.admin.key(),
+                action: "BLACKLIST_ADD".to_string(),
+                details: format!("User {} added to blacklist", user),
+                timestamp: Clock::get()?.unix_timestamp,
+                global_seq: ctx.accounts.config.next_seq()?,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_from_blacklist(ctx: Context<ManageBlacklist>, user: Pubkey) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require_keys_eq!(user, ctx.accounts.user.key(), ErrorCode::InvalidInput);
+
+        let blacklist = &mut ctx.accounts.blacklist;
+        if let Some(index) = blacklist.blacklisted_users.iter().position(|&x| x == user) {
+            blacklist.blacklisted_users.remove(index);
+
+            // Desmarcar na conta do usuário (e encerrar um pedido de revisão)
+            ctx.accounts.user_claim_account.is_blacklisted = false;
+            ctx.accounts.user_claim_account.appeal_requested = false;
+
+            emit!(SecurityEvent {
+                event_type: "USER_UNBLACKLISTED".to_string(),
+                user,
+                reason: "Removed from blacklist by admin".to_string(),
+                timestamp: Clock::get()?.unix_timestamp,
+                global_seq: ctx.accounts.config.next_seq()?,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Fechar a BlacklistEntry vencida de um usuário; o rent volta ao admin
+    // (recipient). Permissionless: qualquer um pode podar, mas só depois do prazo
+    pub fn prune_blacklist_entry(ctx: Context<PruneBlacklistEntry>, user: Pubkey) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(ctx.accounts.blacklist_entry.expired(now), ErrorCode::InvalidInput);
+        let expires_at = ctx.accounts.blacklist_entry.expires_at;
+
+        emit!(SecurityEvent {
+            event_type: "BLACKLIST_ENTRY_PRUNED".to_string(),
+            user,
+            reason: format!(
+                "Temporary ban expired at {} pruned by {}",
+                expires_at,
+                ctx.accounts.caller.key()
+            ),
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Adicionar vários usuários à blacklist de uma vez (ataques coordenados).
+    // As UserClaimAccount já existentes podem ser passadas em remaining_accounts
+    // para que a flag is_blacklisted seja marcada na mesma transação.
+    pub fn add_to_blacklist_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ManageBlacklistBatch<'info>>,
+        users: Vec<Pubkey>,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        let users = dedupe_blacklist_batch(users)?;
+
+        let blacklist = &mut ctx.accounts.blacklist;
+        let mut added: u32 = 0;
+        for user in users.iter() {
+            if blacklist.blacklisted_users.contains(user) {
+                continue;
+            }
+            require!(
+                blacklist.blacklisted_users.len() < MAX_BLACKLIST_SIZE,
+                ErrorCode::BlacklistFull
+            );
+            blacklist.blacklisted_users.push(*user);
+            added += 1;
+        }
+
+        let synced = sync_blacklist_flags(ctx.remaining_accounts, &users, true)?;
+
+        emit!(SecurityEvent {
+            event_type: "USERS_BLACKLISTED_BATCH".to_string(),
+            user: ctx.accounts.admin.key(),
+            reason: format!(
+                "{} requested, {} added, {} user flags synced",
+                users.len(),
+                added,
+                synced
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Remover vários usuários da blacklist de uma vez (mesmo formato do add)
+    pub fn remove_from_blacklist_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ManageBlacklistBatch<'info>>,
+        users: Vec<Pubkey>,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        let users = dedupe_blacklist_batch(users)?;
+
+        let blacklist = &mut ctx.accounts.blacklist;
+        let before = blacklist.blacklisted_users.len();
+        blacklist.blacklisted_users.retain(|user| !users.contains(user));
+        let removed = before - blacklist.blacklisted_users.len();
+
+        let synced = sync_blacklist_flags(ctx.remaining_accounts, &users, false)?;
+
+        emit!(SecurityEvent {
+            event_type: "USERS_UNBLACKLISTED_BATCH".to_string(),
+            user: ctx.accounts.admin.key(),
+            reason: format!(
+                "{} requested, {} removed, {} user flags synced",
+                users.len(),
+                removed,
+                synced
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Usuário banido sinaliza um pedido de revisão para o suporte. Não altera
+    // o banimento; remove_from_blacklist limpa o pedido
+    pub fn request_unban(ctx: Context<RequestUnban>) -> Result<()> {
+        let user = ctx.accounts.user.key();
+        let now = Clock::get()?.unix_timestamp;
+        let temporary_ban = ctx.accounts.blacklist_entry.as_ref()
+            .is_some_and(|entry| !entry.expired(now));
+        let user_claim = &mut ctx.accounts.user_claim_account;
+        require!(
+            user_claim.is_blacklisted
+                || ctx.accounts.blacklist.blacklisted_users.contains(&user)
+                || temporary_ban,
+            ErrorCode::InvalidInput
+        );
+        require!(!user_claim.appeal_requested, ErrorCode::InvalidInput);
+
+        user_claim.appeal_requested = true;
+        user_claim.appeal_timestamp = now;
+
+        emit!(SecurityEvent {
+            event_type: "UNBAN_REQUESTED".to_string(),
+            user,
+            reason: "User requested blacklist review".to_string(),
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Sincronizar a flag is_blacklisted do usuário com a lista autoritativa.
+    // Corrige contas criadas depois do usuário ter sido adicionado à blacklist.
+    pub fn sync_blacklist_flag(ctx: Context<SyncBlacklistFlag>) -> Result<()> {
+        let user = ctx.accounts.user.key();
+        let is_blacklisted = ctx.accounts.blacklist.blacklisted_users.contains(&user);
+
+        let user_claim = &mut ctx.accounts.user_claim_account;
+        if user_claim.is_blacklisted != is_blacklisted {
+            user_claim.is_blacklisted = is_blacklisted;
+
+            emit!(SecurityEvent {
+                event_type: "BLACKLIST_FLAG_SYNCED".to_string(),
+                user,
+                reason: format!("is_blacklisted synced to {}", is_blacklisted),
+                timestamp: Clock::get()?.unix_timestamp,
+                global_seq: ctx.accounts.config.next_seq()?,
+            });
+        }
+
+        Ok(())
+    }
+
+    // View: usuário está na blacklist em qualquer uma das fontes? O resultado
+    // volta via return data (set_return_data) e ajuda a detectar dessincronia
+    pub fn is_blacklisted(ctx: Context<CheckBlacklist>) -> Result<BlacklistStatus> {
+        let user = ctx.accounts.user.key();
+        let in_blacklist_account = ctx.accounts.blacklist.blacklisted_users.contains(&user);
+        let user_claim_flag = ctx.accounts.user_claim_account
+            .as_ref()
+            .map(|user_claim| user_claim.is_blacklisted)
+            .unwrap_or(false);
+        let now = Clock::get()?.unix_timestamp;
+        let temporary_ban = ctx.accounts.blacklist_entry
+            .as_ref()
+            .is_some_and(|entry| !entry.expired(now));
+
+        let status = BlacklistStatus {
+            is_blacklisted: in_blacklist_account || user_claim_flag || temporary_ban,
+            in_blacklist_account,
+            user_claim_flag,
+            temporary_ban,
+        };
+
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("User: {}", user);
+            msg!("Blacklisted: {}", status.is_blacklisted);
+            msg!("In blacklist account: {}", in_blacklist_account);
+            msg!("User claim flag: {}", user_claim_flag);
+        }
+
+        Ok(status)
+    }
+
+    // Checagem de deploy: falha com MintAuthorityMismatch se o mint (principal
+    // ou registrado) ainda não tem o PDA [b"mint_authority"] como authority,
+    // em vez do erro genérico do CPI no primeiro claim
+    pub fn verify_mint_authority(ctx: Context<VerifyMintAuthority>) -> Result<()> {
+        require!(
+            ctx.accounts.token_mint.mint_authority
+                == COption::Some(ctx.accounts.config.mint_authority),
+            ErrorCode::MintAuthorityMismatch
+        );
+
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("Mint: {}", ctx.accounts.token_mint.key());
+            msg!("Mint authority OK: {}", ctx.accounts.config.mint_authority);
+        }
+
+        Ok(())
+    }
+
+    // View para clientes e composição via CPI: retorna um resumo versionado
+    // da config em return data, desacoplado do layout de armazenamento
+    // (a ConfigAccount inteira também não caberia no limite de return data)
+    pub fn get_config(ctx: Context<GetConfig>) -> Result<ConfigView> {
+        let config = &ctx.accounts.config;
+        Ok(ConfigView {
+            version: CONFIG_VIEW_VERSION,
+            admin: config.admin,
+            payment_token_mint: config.payment_token_mint,
+            decimals: config.decimals,
+            emergency_paused: config.is_paused(Clock::get()?.unix_timestamp),
+            max_claim_per_user: config.max_claim_per_user,
+            max_claim_per_week: config.max_claim_per_week,
+            min_claim_amount: config.min_claim_amount,
+            max_claim_amount: config.max_claim_amount,
+            total_supply_limit: config.total_supply_limit,
+            total_minted: config.total_minted,
+            claim_burn_bps: config.claim_burn_bps,
+            claim_fee_bps: config.claim_fee_bps,
+            allowlist_enabled: config.allowlist_enabled,
+        })
+    }
+
+    // Registrar um token adicional com supply e limites próprios
+    pub fn register_token(
+        ctx: Context<RegisterToken>,
+        supply_limit: u64,
+        max_claim_per_user: u64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(supply_limit > 0, ErrorCode::InvalidInput);
+        require!(max_claim_per_user > 0, ErrorCode::InvalidInput);
+        require_keys_neq!(
+            ctx.accounts.token_mint.key(),
+            ctx.accounts.config.payment_token_mint,
+            ErrorCode::InvalidPaymentToken
+        );
+
+        let registered = &mut ctx.accounts.registered_token;
+        registered.mint = ctx.accounts.token_mint.key();
+        registered.supply_limit = supply_limit;
+        registered.total_minted = 0;
+        registered.max_claim_per_user = max_claim_per_user;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "REGISTER_TOKEN".to_string(),
+            details: format!(
+                "Token {} registered (supply_limit={} max_claim_per_user={})",
+                registered.mint, supply_limit, max_claim_per_user
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Ajustar o limite diário por usuário de um token registrado sem
+    // re-registrá-lo (o que zeraria o total_minted)
+    pub fn set_registered_token_limit(
+        ctx: Context<UpdateRegisteredToken>,
+        max_claim_per_user: u64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(max_claim_per_user > 0, ErrorCode::InvalidInput);
+
+        let registered = &mut ctx.accounts.registered_token;
+        let previous = registered.max_claim_per_user;
+        registered.max_claim_per_user = max_claim_per_user;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_REGISTERED_TOKEN_LIMIT".to_string(),
+            details: format!(
+                "Token {} max_claim_per_user {} -> {}",
+                registered.mint, previous, max_claim_per_user
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Remover um token do registro
+    pub fn unregister_token(ctx: Context<UnregisterToken>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "UNREGISTER_TOKEN".to_string(),
+            details: format!("Token {} unregistered", ctx.accounts.registered_token.mint),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Registrar o usuário, marcando o início da contagem da idade da conta
+    pub fn register_user(ctx: Context<RegisterUser>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let registration = &mut ctx.accounts.user_registration;
+        registration.user = ctx.accounts.user.key();
+        registration.registered_at = now;
+
+        msg!("Usuário registrado: {}", registration.user);
+
+        Ok(())
+    }
+
+    // Gerenciamento da allowlist
+    pub fn add_to_allowlist(ctx: Context<AddToAllowlist>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let entry = &mut ctx.accounts.allowlist_entry;
+        entry.user = ctx.accounts.user.key();
+        entry.added_at = now;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "ALLOWLIST_ADD".to_string(),
+            details: format!("User {} added to allowlist", entry.user),
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_from_allowlist(ctx: Context<RemoveFromAllowlist>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "ALLOWLIST_REMOVE".to_string(),
+            details: format!("User {} removed from allowlist", ctx.accounts.user.key()),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Solicitar mudança administrativa (com delay de 24h)
+    pub fn request_admin_action(
+        ctx: Context<RequestAdminAction>,
+        action_type: AdminActionType,
+        new_value: Pubkey,
+        new_amount: u64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let pending_action = &mut ctx.accounts.pending_action;
+        pending_action.action_type = action_type;
+        pending_action.new_value = new_value;
+        pending_action.new_amount = new_amount;
+        pending_action.requested_at = Clock::get()?.unix_timestamp;
+        pending_action.executed = false;
+        pending_action.bump = ctx.bumps.pending_action;
+        pending_action.ready_at = pending_action.requested_at
+            .checked_add(ADMIN_ACTION_DELAY_SECONDS)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let ready_at = pending_action.ready_at;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: format!("REQUEST_{:?}", action_type),
+            details: format!(
+                "Requested change to {} (amount {}), ready_at {}",
+                new_value, new_amount, ready_at
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("Admin action requested. Execute after 24h delay for security.");
+        }
+
+        Ok(())
+    }
+
+    // Cancelar a ação pendente (ou fechar uma já executada) devolvendo o rent.
+    // Como o PDA é único por admin, isso libera o slot para a próxima
+    // solicitação; não há como acumular ações pendentes
+    pub fn cancel_admin_action(ctx: Context<CancelAdminAction>) -> Result<()> {
+        let pending_action = &ctx.accounts.pending_action;
+        let action = if pending_action.executed {
+            "CLOSE_ADMIN_ACTION"
+        } else {
+            "CANCEL_ADMIN_ACTION"
+        };
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: action.to_string(),
+            details: format!(
+                "{:?} (new_value {}, amount {}) requested at {}",
+                pending_action.action_type,
+                pending_action.new_value,
+                pending_action.new_amount,
+                pending_action.requested_at
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // View: a ação pendente do admin já pode ser executada? Emite um
+    // AdminActionStatusEvent para o monitoramento alertar no momento certo
+    pub fn check_admin_action(ctx: Context<CheckAdminAction>) -> Result<()> {
+        let pending_action = &ctx.accounts.pending_action;
+        let now = Clock::get()?.unix_timestamp;
+        let executable = !pending_action.executed && now >= pending_action.ready_at;
+
+        emit!(AdminActionStatusEvent {
+            admin: ctx.accounts.admin.key(),
+            action: format!("{:?}", pending_action.action_type),
+            ready_at: pending_action.ready_at,
+            executed: pending_action.executed,
+            executable,
+            timestamp: now,
+        });
+
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("Action: {:?}", pending_action.action_type);
+            msg!("Ready at: {}", pending_action.ready_at);
+            msg!("Executable: {}", executable);
+        }
+
+        Ok(())
+    }
+
+    // Executar mudança administrativa após delay
+    pub fn execute_admin_action(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let pending_action = &ctx.accounts.pending_action;
+        require!(!pending_action.executed, ErrorCode::InvalidInput);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now - pending_action.requested_at >= ADMIN_ACTION_DELAY_SECONDS,
+            ErrorCode::InvalidInput
+        );
+
+        let config = &mut ctx.accounts.config;
+
+        match pending_action.action_type {
+            AdminActionType::ChangeAdmin => {
+                config.admin = pending_action.new_value;
+                emit!(AdminActionEvent {
+                    admin: ctx.accounts.admin.key(),
+                    action: "CHANGE_ADMIN".to_string(),
+                    details: format!("Admin changed to {}", pending_action.new_value),
+                    timestamp: now,
+                    global_seq: config.next_seq()?,
+                });
+            },
+            AdminActionType::ChangeToken => {
+                config.payment_token_mint = pending_action.new_value;
+                emit!(AdminActionEvent {
+                    admin: ctx.accounts.admin.key(),
+                    action: "CHANGE_TOKEN".to_string(),
+                    details: format!("Token changed to {}", pending_action.new_value),
+                    timestamp: now,
+                    global_seq: config.next_seq()?,
+                });
+            },
+            AdminActionType::SetAdminMintCap => {
+                require!(pending_action.new_amount > 0, ErrorCode::InvalidInput);
+                config.max_admin_mint_per_call = pending_action.new_amount;
+                emit!(AdminActionEvent {
+                    admin: ctx.accounts.admin.key(),
+                    action: "SET_ADMIN_MINT_CAP".to_string(),
+                    details: format!(
+                        "max_admin_mint_per_call set to {}",
+                        pending_action.new_amount
+                    ),
+                    timestamp: now,
+                    global_seq: config.next_seq()?,
+                });
+            },
+            AdminActionType::ChangeMaxClaim => {
+                require!(pending_action.new_amount > 0, ErrorCode::InvalidInput);
+                config.max_claim_per_user = pending_action.new_amount;
+                emit!(AdminActionEvent {
+                    admin: ctx.accounts.admin.key(),
+                    action: "CHANGE_MAX_CLAIM".to_string(),
+                    details: format!(
+                        "max_claim_per_user set to {}",
+                        pending_action.new_amount
+                    ),
+                    timestamp: now,
+                    global_seq: config.next_seq()?,
+                });
+            },
+            AdminActionType::ChangeSupplyLimit => {
+                // O novo limite não pode ficar abaixo do que já foi mintado
+                require!(
+                    pending_action.new_amount > 0
+                        && pending_action.new_amount >= config.total_minted,
+                    ErrorCode::InvalidInput
+                );
+                config.total_supply_limit = pending_action.new_amount;
+                emit!(AdminActionEvent {
+                    admin: ctx.accounts.admin.key(),
+                    action: "CHANGE_SUPPLY_LIMIT".to_string(),
+                    details: format!(
+                        "total_supply_limit set to {}",
+                        pending_action.new_amount
+                    ),
+                    timestamp: now,
+                    global_seq: config.next_seq()?,
+                });
+            },
+            AdminActionType::EmergencyWithdraw => {
+                // Requer as contas do tesouro; deve ser executado via emergency_withdraw
+                return err!(ErrorCode::InvalidInput);
+            },
+            AdminActionType::ChangeMintAuthority => {
+                // Requer as contas do mint; deve ser executado via set_mint_authority
+                return err!(ErrorCode::InvalidInput);
+            },
+            AdminActionType::CloseConfig => {
+                // Fecha a conta; deve ser executado via close_config
+                return err!(ErrorCode::InvalidInput);
+            },
+            AdminActionType::SetUserLimit => {
+                // Requer a conta do usuário; deve ser executado via set_user_limit
+                return err!(ErrorCode::InvalidInput);
+            },
+            AdminActionType::ResetUserNonce => {
+                // Requer a conta do usuário; deve ser executado via reset_user_nonce
+                return err!(ErrorCode::InvalidInput);
+            },
+        }
+
+        Ok(())
+    }
+
+    // Fechar a config e devolver o rent (devnet/testes). O chamador precisa
+    // confirmar que não há PDAs dependentes; com tokens já mintados, exige
+    // force e uma ação CloseConfig pendente há mais de 24h
+    pub fn close_config(
+        ctx: Context<CloseConfig>,
+        force: bool,
+        confirm_no_dependents: bool,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(confirm_no_dependents, ErrorCode::InvalidInput);
+
+        let now = Clock::get()?.unix_timestamp;
+        if ctx.accounts.config.total_minted > 0 {
+            require!(force, ErrorCode::InvalidInput);
+
+            let pending_action = ctx.accounts.pending_action.as_mut()
+                .ok_or(ErrorCode::InvalidInput)?;
+            require!(
+                matches!(pending_action.action_type, AdminActionType::CloseConfig),
+                ErrorCode::InvalidInput
+            );
+            require!(!pending_action.executed, ErrorCode::InvalidInput);
+            require!(
+                now - pending_action.requested_at >= ADMIN_ACTION_DELAY_SECONDS,
+                ErrorCode::InvalidInput
+            );
+            pending_action.close(ctx.accounts.admin.to_account_info())?;
+        }
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "CLOSE_CONFIG".to_string(),
+            details: format!(
+                "Config closed (total_minted {}, force {})",
+                ctx.accounts.config.total_minted, force
+            ),
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        msg!("Config fechada, rent devolvido ao admin");
+
+        Ok(())
+    }
+
+    // Ligar/desligar os logs decorativos dos handlers (sem efeito funcional)
+    pub fn set_verbose_logging(ctx: Context<UpdateConfig>, verbose_logging: bool) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.config.verbose_logging = verbose_logging;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_VERBOSE_LOGGING".to_string(),
+            details: format!("verbose_logging set to {}", verbose_logging),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Ligar/desligar a rejeição de um segundo claim do usuário no mesmo slot
+    pub fn set_reject_same_slot_claims(ctx: Context<UpdateConfig>, reject: bool) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.config.reject_same_slot_claims = reject;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_REJECT_SAME_SLOT_CLAIMS".to_string(),
+            details: format!("reject_same_slot_claims set to {}", reject),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Permitir (ou não) assinaturas com timestamp no futuro
+    pub fn set_allow_future_timestamps(ctx: Context<UpdateConfig>, allow: bool) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.config.allow_future_timestamps = allow;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_ALLOW_FUTURE_TIMESTAMPS".to_string(),
+            details: format!("allow_future_timestamps set to {}", allow),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar a curva de emissão (halving)
+    pub fn set_emission_config(
+        ctx: Context<UpdateConfig>,
+        initial_rate: u64,
+        halving_interval_seconds: i64,
+        genesis_ts: i64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(initial_rate > 0, ErrorCode::InvalidInput);
+        require!(halving_interval_seconds >= 0, ErrorCode::InvalidInput);
+
+        ctx.accounts.config.emission = EmissionConfig {
+            initial_rate,
+            halving_interval_seconds,
+            genesis_ts,
+        };
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_EMISSION_CONFIG".to_string(),
+            details: format!(
+                "initial_rate={} halving_interval_seconds={} genesis_ts={}",
+                initial_rate, halving_interval_seconds, genesis_ts
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Congelar uma conta de token. Requer que a freeze authority do mint
+    // seja o PDA [b"freeze_authority"] deste programa.
+    pub fn freeze_account(ctx: Context<FreezeThawTokenAccount>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let bump = ctx.bumps.freeze_authority;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"freeze_authority", &[bump]]];
+
+        let freeze_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            FreezeAccount {
+                account: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                authority: ctx.accounts.freeze_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        token_interface::freeze_account(freeze_ctx)?;
+
+        emit!(SecurityEvent {
+            event_type: "ACCOUNT_FROZEN".to_string(),
+            user: ctx.accounts.token_account.owner,
+            reason: format!("Token account {} frozen by admin", ctx.accounts.token_account.key()),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Descongelar uma conta de token previamente congelada
+    pub fn thaw_account(ctx: Context<FreezeThawTokenAccount>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let bump = ctx.bumps.freeze_authority;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"freeze_authority", &[bump]]];
+
+        let thaw_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            ThawAccount {
+                account: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                authority: ctx.accounts.freeze_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        token_interface::thaw_account(thaw_ctx)?;
+
+        emit!(SecurityEvent {
+            event_type: "ACCOUNT_THAWED".to_string(),
+            user: ctx.accounts.token_account.owner,
+            reason: format!("Token account {} thawed by admin", ctx.accounts.token_account.key()),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar a taxa de claim mintada ao tesouro (até MAX_CLAIM_FEE_BPS)
+    pub fn set_claim_fee_bps(ctx: Context<UpdateConfig>, claim_fee_bps: u16) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(claim_fee_bps <= MAX_CLAIM_FEE_BPS, ErrorCode::InvalidInput);
+
+        ctx.accounts.config.claim_fee_bps = claim_fee_bps;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_CLAIM_FEE_BPS".to_string(),
+            details: format!("claim_fee_bps set to {}", claim_fee_bps),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Trocar a chave do backend que assina claims e queimas (rotação de chave)
+    pub fn set_backend_authority(
+        ctx: Context<UpdateConfig>,
+        backend_authority: Pubkey,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(backend_authority != Pubkey::default(), ErrorCode::InvalidInput);
+
+        let previous = ctx.accounts.config.backend_authority;
+        ctx.accounts.config.backend_authority = backend_authority;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_BACKEND_AUTHORITY".to_string(),
+            details: format!("backend_authority changed from {} to {}", previous, backend_authority),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar o vesting de claims grandes: líquido acima de
+    // instant_claim_threshold fica no escrow por escrow_release_delay segundos
+    pub fn set_escrow_params(
+        ctx: Context<UpdateConfig>,
+        instant_claim_threshold: u64,
+        escrow_release_delay: i64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(escrow_release_delay >= 0, ErrorCode::InvalidInput);
+
+        let config = &mut ctx.accounts.config;
+        config.instant_claim_threshold = instant_claim_threshold;
+        config.escrow_release_delay = escrow_release_delay;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_ESCROW_PARAMS".to_string(),
+            details: format!(
+                "instant_claim_threshold={} escrow_release_delay={}",
+                instant_claim_threshold, escrow_release_delay
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar a taxa de queima aplicada a cada claim
+    pub fn set_claim_burn_bps(ctx: Context<UpdateConfig>, claim_burn_bps: u16) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            claim_burn_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidInput
+        );
+
+        ctx.accounts.config.claim_burn_bps = claim_burn_bps;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_CLAIM_BURN_BPS".to_string(),
+            details: format!("claim_burn_bps set to {}", claim_burn_bps),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Definir o papel de claim de um usuário (multiplicador ou ilimitado e
+    // isenção da taxa do tesouro)
+    pub fn set_claim_role(
+        ctx: Context<SetClaimRole>,
+        limit_multiplier: u16,
+        unlimited: bool,
+        fee_exempt: bool,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(limit_multiplier > 0 || unlimited, ErrorCode::InvalidInput);
+
+        let role = &mut ctx.accounts.claim_role;
+        role.user = ctx.accounts.user.key();
+        role.limit_multiplier = limit_multiplier;
+        role.unlimited = unlimited;
+        role.fee_exempt = fee_exempt;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_CLAIM_ROLE".to_string(),
+            details: format!(
+                "User {} limit_multiplier={} unlimited={} fee_exempt={}",
+                role.user, limit_multiplier, unlimited, fee_exempt
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Transferir a mint authority do mint (timelock via ChangeMintAuthority).
+    // new_value == Pubkey::default() remove a authority de vez (desativa o
+    // mint para sempre) e só é aceito com allow_disable = true
+    pub fn set_mint_authority(ctx: Context<SetMintAuthority>, allow_disable: bool) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let pending_action = &ctx.accounts.pending_action;
+        require!(
+            matches!(pending_action.action_type, AdminActionType::ChangeMintAuthority),
+            ErrorCode::InvalidInput
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now - pending_action.requested_at >= ADMIN_ACTION_DELAY_SECONDS,
+            ErrorCode::InvalidInput
+        );
+
+        let new_authority = pending_action.new_value;
+        let disable = new_authority == Pubkey::default();
+        require!(!disable || allow_disable, ErrorCode::MintDisableNotConfirmed);
+
+        let mint_authority_bump = ctx.accounts.config.mint_authority_bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"mint_authority", &[mint_authority_bump]]];
+
+        let set_authority_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: ctx.accounts.mint_authority.to_account_info(),
+                account_or_mint: ctx.accounts.token_mint.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        let new_authority = if disable { None } else { Some(new_authority) };
+        token_interface::set_authority(set_authority_ctx, AuthorityType::MintTokens, new_authority)?;
+
+        // A config acompanha a authority real do mint: claims e mints passam a
+        // exigir a assinatura da nova authority (can_sign_mint) e
+        // verify_mint_authority reflete a rotação
+        ctx.accounts.config.mint_authority = new_authority.unwrap_or_default();
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "CHANGE_MINT_AUTHORITY".to_string(),
+            details: format!("Mint authority changed to {:?}", new_authority),
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Ativar/desativar o limite baseado no supply real do mint
+    pub fn set_enforce_mint_supply(ctx: Context<UpdateConfig>, enforce: bool) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.config.enforce_mint_supply = enforce;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_ENFORCE_MINT_SUPPLY".to_string(),
+            details: format!("enforce_mint_supply set to {}", enforce),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Permitir mints administrativos de recuperação durante a pausa de
+    // emergência (claims dos usuários continuam bloqueados)
+    pub fn set_allow_admin_mint_while_paused(ctx: Context<UpdateConfig>, allow: bool) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.config.allow_admin_mint_while_paused = allow;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_ALLOW_ADMIN_MINT_WHILE_PAUSED".to_string(),
+            details: format!("allow_admin_mint_while_paused set to {}", allow),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar o limite semanal por usuário do token principal (0 desativa)
+    pub fn set_max_claim_per_week(ctx: Context<UpdateConfig>, max_claim_per_week: u64) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.config.max_claim_per_week = max_claim_per_week;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_MAX_CLAIM_PER_WEEK".to_string(),
+            details: format!("max_claim_per_week set to {}", max_claim_per_week),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar o mínimo e o máximo por transação de claim
+    pub fn set_claim_amount_bounds(
+        ctx: Context<UpdateConfig>,
+        min_claim_amount: u64,
+        max_claim_amount: u64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(min_claim_amount > 0, ErrorCode::InvalidInput);
+        require!(min_claim_amount <= max_claim_amount, ErrorCode::InvalidInput);
+
+        let config = &mut ctx.accounts.config;
+        config.min_claim_amount = min_claim_amount;
+        config.max_claim_amount = max_claim_amount;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_CLAIM_AMOUNT_BOUNDS".to_string(),
+            details: format!("min={} max={}", min_claim_amount, max_claim_amount),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Trocar a raiz da allowlist merkle de uma vez (zeros desativa as provas).
+    // Provas geradas para a raiz anterior deixam de valer imediatamente
+    pub fn set_merkle_root(ctx: Context<UpdateConfig>, merkle_root: [u8; 32]) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.config.merkle_root = merkle_root;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_MERKLE_ROOT".to_string(),
+            details: format!(
+                "merkle_root set to {}",
+                merkle_root.iter().map(|b| format!("{:02x}", b)).collect::<String>()
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Ativar/desativar o modo allowlist
+    pub fn set_allowlist_enabled(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.config.allowlist_enabled = enabled;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_ALLOWLIST_ENABLED".to_string(),
+            details: format!("allowlist_enabled set to {}", enabled),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Criar a metadata (Metaplex) do token de recompensa, com o PDA
+    // [b"mint_authority"] como mint authority e update authority
+    pub fn create_token_metadata(
+        ctx: Context<CreateTokenMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !name.is_empty() && name.len() <= MAX_METADATA_NAME_LEN,
+            ErrorCode::InvalidMetadata
+        );
+        require!(
+            !symbol.is_empty() && symbol.len() <= MAX_METADATA_SYMBOL_LEN,
+            ErrorCode::InvalidMetadata
+        );
+        require!(uri.len() <= MAX_METADATA_URI_LEN, ErrorCode::InvalidMetadata);
+
+        let mint_authority_bump = ctx.accounts.config.mint_authority_bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"mint_authority", &[mint_authority_bump]]];
+        let metadata_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                mint_authority: ctx.accounts.mint_authority.to_account_info(),
+                payer: ctx.accounts.admin.to_account_info(),
+                update_authority: ctx.accounts.mint_authority.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        let data = DataV2 {
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        };
+
+        create_metadata_accounts_v3(metadata_ctx, data, true, true, None)?;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "CREATE_TOKEN_METADATA".to_string(),
+            details: format!("name={} symbol={} uri={}", name, symbol, uri),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        msg!("Metadata do token criada com sucesso");
+
+        Ok(())
+    }
+
+    // Criar o tesouro: ATA do token principal cuja autoridade é o PDA [b"treasury"]
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.config.treasury_bump = ctx.bumps.treasury_authority;
+
+        // O PDA do tesouro também recebe lamports (sweep_lamports). Uma conta
+        // do sistema abaixo do mínimo isento de rent é rejeitada pelo runtime,
+        // então o admin deixa o PDA já isento
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        let shortfall =
+            rent_exempt_minimum.saturating_sub(ctx.accounts.treasury_authority.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: ctx.accounts.treasury_authority.to_account_info(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "INITIALIZE_TREASURY".to_string(),
+            details: format!(
+                "Treasury {} created for mint {}",
+                ctx.accounts.treasury_token_account.key(),
+                ctx.accounts.token_mint.key()
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        msg!("Tesouro inicializado com sucesso");
+
+        Ok(())
+    }
+
+    // Depositar tokens no tesouro do programa
+    pub fn deposit_to_treasury(ctx: Context<DepositToTreasury>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidPaymentAmount);
+        require!(
+            ctx.accounts.depositor_token_account.amount >= amount,
+            ErrorCode::InsufficientFunds
+        );
+
+        transfer_to_treasury(
+            &ctx.accounts.token_program,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.depositor_token_account,
+            &ctx.accounts.treasury_token_account,
+            ctx.accounts.depositor.to_account_info(),
+            amount,
+        )?;
+
+        emit!(TreasuryEvent {
+            action: "DEPOSIT".to_string(),
+            account: ctx.accounts.depositor.key(),
+            token_mint: ctx.accounts.token_mint.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Saque emergencial do tesouro para a carteira definida na ação
+    // EmergencyWithdraw pendente (após o delay de 24h). O PDA assina a transferência.
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let pending_action = &ctx.accounts.pending_action;
+        require!(
+            matches!(pending_action.action_type, AdminActionType::EmergencyWithdraw),
+            ErrorCode::InvalidInput
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now - pending_action.requested_at >= ADMIN_ACTION_DELAY_SECONDS,
+            ErrorCode::InvalidInput
+        );
+
+        // O destino precisa ser a ATA da carteira aprovada na ação pendente
+        require_keys_eq!(
+            ctx.accounts.destination_token_account.owner,
+            pending_action.new_value,
+            ErrorCode::InvalidTokenAccount
+        );
+        require!(amount > 0, ErrorCode::InvalidPaymentAmount);
+        require!(
+            ctx.accounts.treasury_token_account.amount >= amount,
+            ErrorCode::InsufficientFunds
+        );
+
+        let treasury_bump = ctx.accounts.config.treasury_bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"treasury", &[treasury_bump]]];
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.treasury_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.destination_token_account.to_account_info(),
+                authority: ctx.accounts.treasury_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+
+        let destination = ctx.accounts.destination_token_account.owner;
+        emit!(TreasuryEvent {
+            action: "EMERGENCY_WITHDRAW".to_string(),
+            account: destination,
+            token_mint: ctx.accounts.token_mint.key(),
+            amount,
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "EMERGENCY_WITHDRAW".to_string(),
+            details: format!("Withdrew {} from treasury to {}", amount, destination),
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Mover para o PDA do tesouro os lamports de um PDA do programa que
+    // excedem o mínimo isento de rent (rent superfinanciado ou transferências
+    // diretas). Saldos de tokens não são tocados. Só contas do protocolo
+    // pagas pelo admin podem ser varridas; contas pagas por usuários não
+    pub fn sweep_lamports(ctx: Context<SweepLamports>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let source = ctx.accounts.source.to_account_info();
+        {
+            let data = source.try_borrow_data()?;
+            require!(data.len() >= 8, ErrorCode::InvalidInput);
+            let discriminator = &data[..8];
+            require!(
+                [
+                    ConfigAccount::DISCRIMINATOR,
+                    StatsAccount::DISCRIMINATOR,
+                    BlacklistAccount::DISCRIMINATOR,
+                    RegisteredToken::DISCRIMINATOR,
+                ]
+                .iter()
+                .any(|known| known.as_ref() == discriminator),
+                ErrorCode::InvalidInput
+            );
+        }
+
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(source.data_len());
+        let excess = source.lamports().saturating_sub(rent_exempt_minimum);
+        require!(excess > 0, ErrorCode::InvalidPaymentAmount);
+
+        // O tesouro precisa terminar isento de rent (initialize_treasury já o
+        // financia; tesouros antigos precisam de um depósito antes)
+        let treasury_balance = ctx.accounts.treasury_authority.lamports()
+            .checked_add(excess)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            treasury_balance >= Rent::get()?.minimum_balance(0),
+            ErrorCode::InsufficientFunds
+        );
+
+        // O programa é dono da conta de origem, então pode debitar diretamente
+        **source.try_borrow_mut_lamports()? -= excess;
+        **ctx.accounts.treasury_authority.try_borrow_mut_lamports()? += excess;
+
+        let now = Clock::get()?.unix_timestamp;
+        emit!(TreasuryEvent {
+            action: "SWEEP_LAMPORTS".to_string(),
+            account: source.key(),
+            token_mint: Pubkey::default(),
+            amount: excess,
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SWEEP_LAMPORTS".to_string(),
+            details: format!("Swept {} lamports from {} to treasury", excess, source.key()),
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Sacar lamports do PDA do tesouro (ex. os varridos por sweep_lamports)
+    // para uma carteira escolhida pelo admin. O PDA continua isento de rent
+    pub fn withdraw_treasury_lamports(
+        ctx: Context<WithdrawTreasuryLamports>,
+        amount: u64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(amount > 0, ErrorCode::InvalidPaymentAmount);
+
+        let available = ctx.accounts.treasury_authority.lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        require!(amount <= available, ErrorCode::InsufficientFunds);
+
+        let treasury_bump = ctx.accounts.config.treasury_bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"treasury", &[treasury_bump]]];
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.treasury_authority.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let destination = ctx.accounts.destination.key();
+        emit!(TreasuryEvent {
+            action: "WITHDRAW_LAMPORTS".to_string(),
+            account: destination,
+            token_mint: Pubkey::default(),
+            amount,
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "WITHDRAW_TREASURY_LAMPORTS".to_string(),
+            details: format!("Withdrew {} lamports from treasury to {}", amount, destination),
+            timestamp: now,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Recuperar tokens de um claim fraudulento dentro da janela de clawback.
+    // Sem a aprovação do dono, só o permanent delegate do Token-2022 pode
+    // queimar: o mint precisa ter o PDA [b"freeze_authority"] como permanent
+    // delegate (mints do SPL Token clássico não suportam clawback; use
+    // freeze_account). A queima sai da ATA de quem recebeu o último
+    // claim; uma conta congelada é descongelada para a queima e congelada de novo.
+    pub fn clawback(ctx: Context<Clawback>, amount: u64) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(amount > 0, ErrorCode::InvalidPaymentAmount);
+
+        let now = Clock::get()?.unix_timestamp;
+        let window = ctx.accounts.config.clawback_window_seconds;
+        require!(
+            window > 0 && now - ctx.accounts.user_claim_account.last_claim_timestamp <= window,
+            ErrorCode::ClawbackWindowExpired
+        );
+        require!(
+            amount <= ctx.accounts.user_claim_account.last_claim_amount,
+            ErrorCode::InvalidPaymentAmount
+        );
+        // O claim revertido precisa ser deste mint (a ATA é a do destinatário
+        // gravado, validada pelas contas)
+        require_keys_eq!(
+            ctx.accounts.user_claim_account.last_claim_mint,
+            ctx.accounts.token_mint.key(),
+            ErrorCode::InvalidPaymentToken
+        );
+        require!(
+            mint_permanent_delegate(&ctx.accounts.token_mint.to_account_info())?
+                == Some(ctx.accounts.freeze_authority.key()),
+            ErrorCode::InvalidDelegate
+        );
+        let was_frozen = ctx.accounts.recipient_token_account.is_frozen();
+
+        let bump = ctx.bumps.freeze_authority;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"freeze_authority", &[bump]]];
+
+        if was_frozen {
+            token_interface::thaw_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                ThawAccount {
+                    account: ctx.accounts.recipient_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    authority: ctx.accounts.freeze_authority.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+
+        burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    from: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: ctx.accounts.freeze_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        if was_frozen {
+            token_interface::freeze_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                FreezeAccount {
+                    account: ctx.accounts.recipient_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    authority: ctx.accounts.freeze_authority.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+
+        let user_claim = &mut ctx.accounts.user_claim_account;
+        user_claim.last_claim_amount = user_claim.last_claim_amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Devolver ao contador do mint do claim (token registrado ou principal)
+        match ctx.accounts.registered_token.as_mut() {
+            Some(registered) => {
+                registered.total_minted = registered.total_minted
+                    .checked_sub(amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+            None => {
+                ctx.accounts.config.total_minted = ctx.accounts.config.total_minted
+                    .checked_sub(amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+
+        let config = &mut ctx.accounts.config;
+        emit!(SecurityEvent {
+            event_type: "CLAWBACK".to_string(),
+            user: ctx.accounts.user.key(),
+            reason: format!(
+                "{} tokens clawed back by admin from {}",
+                amount,
+                ctx.accounts.recipient.key()
+            ),
+            timestamp: now,
+            global_seq: config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar a janela de clawback após cada claim
+    pub fn set_clawback_window(ctx: Context<UpdateConfig>, clawback_window_seconds: i64) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(clawback_window_seconds >= 0, ErrorCode::InvalidInput);
+
+        ctx.accounts.config.clawback_window_seconds = clawback_window_seconds;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_CLAWBACK_WINDOW".to_string(),
+            details: format!("clawback_window_seconds set to {}", clawback_window_seconds),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar o tamanho máximo de um batch_mint_tokens
+    pub fn set_max_batch_size(ctx: Context<UpdateConfig>, max_batch_size: u16) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(max_batch_size > 0, ErrorCode::InvalidInput);
+
+        ctx.accounts.config.max_batch_size = max_batch_size;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_MAX_BATCH_SIZE".to_string(),
+            details: format!("max_batch_size set to {}", max_batch_size),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar a idade mínima do registro para claimar (0 = desativado)
+    pub fn set_account_age_requirement(
+        ctx: Context<UpdateConfig>,
+        account_age_requirement_seconds: i64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(account_age_requirement_seconds >= 0, ErrorCode::InvalidInput);
+
+        ctx.accounts.config.account_age_requirement_seconds = account_age_requirement_seconds;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_ACCOUNT_AGE_REQUIREMENT".to_string(),
+            details: format!(
+                "account_age_requirement_seconds set to {}",
+                account_age_requirement_seconds
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: ctx.accounts.config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar o limite diário de uma categoria de recompensa (0 = desativar)
+    pub fn set_category_daily_cap(
+        ctx: Context<UpdateConfig>,
+        category: u8,
+        daily_cap: u64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            category != DEFAULT_CLAIM_CATEGORY && (category as usize) < MAX_CLAIM_CATEGORIES,
+            ErrorCode::InvalidClaimCategory
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.category_daily_caps[category as usize] = daily_cap;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_CATEGORY_DAILY_CAP".to_string(),
+            details: format!("category {} daily cap set to {}", category, daily_cap),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar o limite de claims por minuto do circuit breaker (0 = desativado)
+    pub fn set_claims_per_minute_threshold(
+        ctx: Context<UpdateConfig>,
+        claims_per_minute_threshold: u32,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.claims_per_minute_threshold = claims_per_minute_threshold;
+        config.claims_in_current_minute = 0;
+        config.minute_window_start = 0;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_CLAIMS_PER_MINUTE_THRESHOLD".to_string(),
+            details: format!(
+                "claims_per_minute_threshold set to {}",
+                claims_per_minute_threshold
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Configurar os guardians da troca emergencial de admin. Se já houver
+    // guardians, a mudança também exige o threshold atual de assinaturas
+    // (remaining_accounts), para que um admin comprometido não os remova
+    pub fn set_guardians(
+        ctx: Context<UpdateConfig>,
+        guardians: Vec<Pubkey>,
+        guardian_threshold: u8,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(guardians.len() <= MAX_GUARDIANS, ErrorCode::TooManyGuardians);
+        require!(
+            guardian_threshold as usize <= guardians.len(),
+            ErrorCode::InvalidInput
+        );
+        require!(
+            guardians.is_empty() || guardian_threshold > 0,
+            ErrorCode::InvalidInput
+        );
+        for (i, guardian) in guardians.iter().enumerate() {
+            require!(*guardian != Pubkey::default(), ErrorCode::InvalidInput);
+            require!(!guardians[..i].contains(guardian), ErrorCode::InvalidInput);
+        }
+
+        let config = &mut ctx.accounts.config;
+        if config.guardian_threshold > 0 {
+            require!(
+                config.count_guardian_signers(ctx.remaining_accounts)
+                    >= config.guardian_threshold as usize,
+                ErrorCode::InsufficientGuardianSignatures
+            );
+        }
+
+        config.guardians = guardians;
+        config.guardian_threshold = guardian_threshold;
+
+        emit!(AdminActionEvent {
+            admin: ctx.accounts.admin.key(),
+            action: "SET_GUARDIANS".to_string(),
+            details: format!(
+                "{} guardians, threshold {}",
+                config.guardians.len(),
+                guardian_threshold
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Troca emergencial de admin sem timelock, aprovada por M-of-N guardians
+    // (assinantes passados em remaining_accounts)
+    pub fn transfer_admin_emergency(
+        ctx: Context<TransferAdminEmergency>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        #[cfg(not(feature = "structured-logs"))]
+        if ctx.accounts.config.verbose_logging {
+            msg!("=== TRANSFER ADMIN EMERGENCY ===");
+            msg!("New Admin: {}", new_admin);
+        }
+
+        require!(new_admin != Pubkey::default(), ErrorCode::InvalidInput);
+
+        let config = &mut ctx.accounts.config;
+        require!(config.guardian_threshold > 0, ErrorCode::InsufficientGuardianSignatures);
+
+        let signers = config.count_guardian_signers(ctx.remaining_accounts);
+        require!(
+            signers >= config.guardian_threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures
+        );
+
+        let old_admin = config.admin;
+        config.admin = new_admin;
+
+        emit!(SecurityEvent {
+            event_type: "EMERGENCY_ADMIN_TRANSFER".to_string(),
+            user: new_admin,
+            reason: format!(
+                "Admin changed from {} to {} by {} of {} guardians",
+                old_admin,
+                new_admin,
+                signers,
+                config.guardians.len()
+            ),
+            timestamp: Clock::get()?.unix_timestamp,
+            global_seq: config.next_seq()?,
+        });
+
+        #[cfg(not(feature = "structured-logs"))]
+        if config.verbose_logging {
+            msg!("⚠️ ADMIN TROCADO EMERGENCIALMENTE PELOS GUARDIANS");
+        }
+
+        Ok(())
+    }
+
+    // Circuit breaker - pausa automática se detectar atividade suspeita
+    // duration_secs > 0 faz a pausa expirar sozinha (uma pausa esquecida não
+    // trava o jogo); 0 pausa por tempo indefinido até emergency_resume.
+    // Chamar de novo rearma o prazo
+    pub fn emergency_pause(
+        ctx: Context<EmergencyPause>,
+        reason: String,
+        duration_secs: i64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(reason.len() <= MAX_PAUSE_REASON_LEN, ErrorCode::PauseReasonTooLong);
+        require!(duration_secs >= 0, ErrorCode::InvalidInput);
+
+        let now = Clock::get()?.unix_timestamp;
+        let pause_expires_at = if duration_secs == 0 {
+            0
+        } else {
+            now.checked_add(duration_secs).ok_or(ErrorCode::MathOverflow)?
+        };
+        let config = &mut ctx.accounts.config;
+        config.emergency_paused = true;
+        config.pause_reason = reason.clone();
+        config.paused_at = now;
+        config.pause_expires_at = pause_expires_at;
+
+        emit!(SecurityEvent {
+            event_type: "EMERGENCY_PAUSE".to_string(),
+            user: ctx.accounts.admin.key(),
+            reason,
+            timestamp: now,
+            global_seq: config.next_seq()?,
+        });
+
+        Ok(())
+    }
+
+    // Retomar a operação após uma pausa de emergência
+    pub fn emergency_resume(ctx: Context<EmergencyPause>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let config = &mut ctx.accounts.config;
+        config.emergency_paused = false;
+        config.pause_reason = String::new();
+        config.paused_at = 0;
+        config.pause_expires_at = 0;
+        // O circuit breaker recomeça com uma janela nova
+        config.claims_in_current_minute = 0;
+        config.minute_window_start = 0;
+
+        emit!(SecurityEvent {
+            event_type: "EMERGENCY_RESUME".to_string(),
+            user: ctx.accounts.admin.key(),
+            reason: "System resumed by admin".to_string(),
+            timestamp: now,
+            global_seq: config.next_seq()?,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(args: ClaimArgs)]
+pub struct ClaimTokens<'info> {
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        associated_token::mint = token_mint,
+        associated_token::authority = claimer,
+        associated_token::token_program = token_program,
+        // Erros explícitos para contas não canônicas (o erro do Anchor é genérico)
+        constraint = claimer_token_account.mint == token_mint.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = claimer_token_account.owner == claimer.key() @ ErrorCode::InvalidTokenAccount,
+    )]
+    pub claimer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // ATA do destinatário quando o claim é enviado para outra carteira
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == token_mint.key() @ ErrorCode::InvalidTokenAccount,
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // ATA do tesouro; exigida apenas com claim_fee_bps > 0
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == token_mint.key() @ ErrorCode::InvalidTokenAccount,
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Token registrado (ausente para o token principal da config)
+    #[account(
+        mut,
+        seeds = [b"registered_token", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub registered_token: Option<Account<'info, RegisteredToken>>,
+
+    // Para tokens registrados os contadores são por (usuário, mint); para o
+    // token principal o sufixo é vazio e o PDA continua [b"user_claim", claimer]
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + UserClaimAccount::INIT_SPACE,
+        seeds = [
+            b"user_claim",
+            claimer.key().as_ref(),
+            registered_token.as_ref().map(|r| r.mint.as_ref()).unwrap_or(&[]),
+        ],
+        bump,
+    )]
+    pub user_claim_account: Account<'info, UserClaimAccount>,
+
+    /// CHECK: Conta principal [b"user_claim", claimer] com banimento e suspensão;
+    /// lida só em claims de token registrado e pode ainda não existir (ver load_user_claim)
+    #[account(
+        seeds = [b"user_claim", claimer.key().as_ref()],
+        bump,
+    )]
+    pub user_status: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"claim_role", claimer.key().as_ref()],
+        bump,
+    )]
+    pub claim_role: Option<Account<'info, ClaimRole>>,
+
+    #[account(
+        seeds = [b"allowlist", claimer.key().as_ref()],
+        bump,
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+
+    #[account(
+        seeds = [b"user_registration", claimer.key().as_ref()],
+        bump,
+    )]
+    pub user_registration: Option<Account<'info, UserRegistration>>,
+
+    // Escrow de vesting; exigido quando o claim passa de instant_claim_threshold
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + EscrowAccount::INIT_SPACE,
+        seeds = [b"escrow", claimer.key().as_ref()],
+        bump,
+    )]
+    pub escrow: Option<Account<'info, EscrowAccount>>,
+
+    #[account(
+        init,
+        payer = claimer,
+        space = 8 + ClaimedSession::INIT_SPACE,
+        seeds = [b"claimed_session", args.game_session_hash.as_ref()],
+        bump,
+    )]
+    pub claimed_session: Option<Account<'info, ClaimedSession>>,
+
+    // Métricas; opcional para claims/burns/mints funcionarem antes de initialize_stats
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump,
+    )]
+    pub stats: Option<Account<'info, StatsAccount>>,
+
+    /// CHECK: PDA da blacklist; pode ainda não ter sido inicializado (ver blacklist_contains)
+    #[account(
+        seeds = [b"blacklist"],
+        bump,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Banimento temporário do claimer; pode não existir (ver blacklist_entry_active)
+    #[account(
+        seeds = [b"blacklist_entry", claimer.key().as_ref()],
+        bump,
+    )]
+    pub blacklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Chave do backend; precisa ser a registrada na config
+    #[account(address = config.backend_authority @ ErrorCode::Unauthorized)]
+    pub backend_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Mint authority configurada; precisa ser a authority atual do mint
+    #[account(
+        constraint = config.can_sign_mint(&mint_authority) @ ErrorCode::MintAuthorityMismatch,
+        constraint = token_mint.mint_authority == COption::Some(config.mint_authority)
+            @ ErrorCode::MintAuthorityMismatch,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.accepts_mint(&token_mint.key(), registered_token.as_deref())
+            @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    /// CHECK: This is the Solana Instructions Sysvar Account for signature verification
+    #[account(address = sysvar_instructions::ID)]
+    pub sysvar_instructions: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SimulateClaim<'info> {
+    /// CHECK: Usuário cujo claim está sendo simulado
+    pub claimer: UncheckedAccount<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"user_claim", claimer.key().as_ref()],
+        bump = user_claim_account.bump,
+    )]
+    pub user_claim_account: Option<Account<'info, UserClaimAccount>>,
+
+    #[account(
+        seeds = [b"claim_role", claimer.key().as_ref()],
+        bump,
+    )]
+    pub claim_role: Option<Account<'info, ClaimRole>>,
+
+    #[account(
+        seeds = [b"allowlist", claimer.key().as_ref()],
+        bump,
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+
+    #[account(
+        seeds = [b"user_registration", claimer.key().as_ref()],
+        bump,
+    )]
+    pub user_registration: Option<Account<'info, UserRegistration>>,
+
+    /// CHECK: PDA da blacklist; pode ainda não ter sido inicializado (ver blacklist_contains)
+    #[account(
+        seeds = [b"blacklist"],
+        bump,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Banimento temporário do claimer; pode não existir (ver blacklist_entry_active)
+    #[account(
+        seeds = [b"blacklist_entry", claimer.key().as_ref()],
+        bump,
+    )]
+    pub blacklist_entry: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseEscrow<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", user.key().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, EscrowAccount>,
+
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == token_mint.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidTokenAccount,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Mint authority configurada; precisa ser a authority atual do mint
+    #[account(
+        constraint = config.can_sign_mint(&mint_authority) @ ErrorCode::MintAuthorityMismatch,
+        constraint = token_mint.mint_authority == COption::Some(config.mint_authority)
+            @ ErrorCode::MintAuthorityMismatch,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseClaimedSession<'info> {
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = claimer,
+        seeds = [b"claimed_session", claimed_session.session_hash.as_ref()],
+        bump = claimed_session.bump,
+        constraint = claimed_session.claimer == claimer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub claimed_session: Account<'info, ClaimedSession>,
+}
+
+#[derive(Accounts)]
+#[instruction(payment_token_mint: Pubkey)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    // Mint do token principal, lido para registrar os decimals
+    #[account(address = payment_token_mint @ ErrorCode::InvalidPaymentToken)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ConfigAccount::INIT_SPACE,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    /// CHECK: Mint authority PDA; endereço e bump são registrados na config
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BurnTokens<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub payment_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub payer_payment_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UserBurnAccount::INIT_SPACE,
+        seeds = [b"user_burn", payer.key().as_ref()],
+        bump,
+    )]
+    pub user_burn_account: Account<'info, UserBurnAccount>,
+
+    #[account(
+        seeds = [b"registered_token", payment_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub registered_token: Option<Account<'info, RegisteredToken>>,
+
+    /// CHECK: Chave do backend; precisa ser a registrada na config
+    #[account(address = config.backend_authority @ ErrorCode::Unauthorized)]
+    pub backend_authority: UncheckedAccount<'info>,
+
+    // Métricas; opcional para claims/burns/mints funcionarem antes de initialize_stats
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump,
+    )]
+    pub stats: Option<Account<'info, StatsAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.payment_token_mint != Pubkey::default()
+            @ ErrorCode::PaymentTokenNotConfigured,
+        constraint = config.accepts_mint(&payment_token_mint.key(), registered_token.as_deref())
+            @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    /// CHECK: This is the Solana Instructions Sysvar Account for signature verification
+    #[account(address = sysvar_instructions::ID)]
+    pub sysvar_instructions: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BurnFromDelegate<'info> {
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    /// CHECK: Dono da conta de token; validado pela derivação da ATA
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payment_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = delegate,
+        space = 8 + UserBurnAccount::INIT_SPACE,
+        seeds = [b"user_burn", owner.key().as_ref()],
+        bump,
+    )]
+    pub user_burn_account: Account<'info, UserBurnAccount>,
+
+    #[account(
+        seeds = [b"registered_token", payment_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub registered_token: Option<Account<'info, RegisteredToken>>,
+
+    /// CHECK: Chave do backend; precisa ser a registrada na config
+    #[account(address = config.backend_authority @ ErrorCode::Unauthorized)]
+    pub backend_authority: UncheckedAccount<'info>,
+
+    // Métricas; opcional para claims/burns/mints funcionarem antes de initialize_stats
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump,
+    )]
+    pub stats: Option<Account<'info, StatsAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.accepts_mint(&payment_token_mint.key(), registered_token.as_deref())
+            @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    /// CHECK: This is the Solana Instructions Sysvar Account for signature verification
+    #[account(address = sysvar_instructions::ID)]
+    pub sysvar_instructions: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintTokens<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    // Paga o rent da ATA do destinatário quando ela ainda não existe. Pode ser
+    // o próprio admin ou outra carteira (ex. um pagador de airdrops)
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    // Criada se necessário, para airdrops sem ATAs pré-criadas
+    #[account(
+        init_if_needed,
+        payer = rent_payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+        constraint = recipient_token_account.owner == recipient.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = recipient_token_account.key()
+            == get_associated_token_address_with_program_id(
+                &recipient.key(),
+                &token_mint.key(),
+                &token_program.key(),
+            )
+            @ ErrorCode::InvalidTokenAccount,
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Conta do destinatário dos tokens
+    pub recipient: UncheckedAccount<'info>,
+
+    // Token registrado (ausente para o token principal da config)
+    #[account(
+        mut,
+        seeds = [b"registered_token", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub registered_token: Option<Account<'info, RegisteredToken>>,
+
+    /// CHECK: Mint authority configurada; precisa ser a authority atual do mint
+    #[account(
+        constraint = config.can_sign_mint(&mint_authority) @ ErrorCode::MintAuthorityMismatch,
+        constraint = token_mint.mint_authority == COption::Some(config.mint_authority)
+            @ ErrorCode::MintAuthorityMismatch,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    // Métricas; opcional para claims/burns/mints funcionarem antes de initialize_stats
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump,
+    )]
+    pub stats: Option<Account<'info, StatsAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+        constraint = config.accepts_mint(&token_mint.key(), registered_token.as_deref())
+            @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageBlacklistBatch<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"blacklist"],
+        bump = blacklist.bump,
+    )]
+    pub blacklist: Account<'info, BlacklistAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct BatchMintTokens<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Mint authority configurada; precisa ser a authority atual do mint
+    #[account(
+        constraint = config.can_sign_mint(&mint_authority) @ ErrorCode::MintAuthorityMismatch,
+        constraint = token_mint.mint_authority == COption::Some(config.mint_authority)
+            @ ErrorCode::MintAuthorityMismatch,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    // Métricas; opcional para claims/burns/mints funcionarem antes de initialize_stats
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump,
+    )]
+    pub stats: Option<Account<'info, StatsAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetUserLimit<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Usuário que recebe o limite personalizado
+    pub user: UncheckedAccount<'info>,
+
+    // Token registrado (ausente para o token principal da config)
+    #[account(
+        seeds = [b"registered_token", registered_token.mint.as_ref()],
+        bump,
+    )]
+    pub registered_token: Option<Account<'info, RegisteredToken>>,
+
+    // Pode ser criada antes do primeiro claim do usuário
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + UserClaimAccount::INIT_SPACE,
+        seeds = [
+            b"user_claim",
+            user.key().as_ref(),
+            registered_token.as_ref().map(|r| r.mint.as_ref()).unwrap_or(&[]),
+        ],
+        bump,
+    )]
+    pub user_claim_account: Account<'info, UserClaimAccount>,
+
+    // Exigida apenas para limites acima de LARGE_USER_LIMIT_MULTIPLIER x padrão
+    #[account(
+        mut,
+        seeds = [b"pending_action", admin.key().as_ref()],
+        bump = pending_action.bump,
+    )]
+    pub pending_action: Option<Account<'info, PendingAdminAction>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SuspendUser<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Usuário a ser suspenso
+    pub user: UncheckedAccount<'info>,
+
+    // Conta principal do usuário: a suspensão vale para todos os tokens.
+    // Pode ser criada antes do primeiro claim do usuário
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + UserClaimAccount::INIT_SPACE,
+        seeds = [b"user_claim", user.key().as_ref()],
+        bump,
+    )]
+    pub user_claim_account: Account<'info, UserClaimAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResetUserLimits<'info> {
+    pub admin: Signer<'info>,
+
+    /// CHECK: Usuário cujos contadores serão resetados
+    pub user: UncheckedAccount<'info>,
+
+    // Token registrado (ausente para o token principal da config)
+    #[account(
+        seeds = [b"registered_token", registered_token.mint.as_ref()],
+        bump,
+    )]
+    pub registered_token: Option<Account<'info, RegisteredToken>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user_claim",
+            user.key().as_ref(),
+            registered_token.as_ref().map(|r| r.mint.as_ref()).unwrap_or(&[]),
+        ],
+        bump = user_claim_account.bump,
+    )]
+    pub user_claim_account: Account<'info, UserClaimAccount>,
+
+    #[account(
+        seeds = [b"blacklist"],
+        bump = blacklist.bump,
+    )]
+    pub blacklist: Account<'info, BlacklistAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ResetUserNonce<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Usuário cujo nonce será ressincronizado
+    pub user: UncheckedAccount<'info>,
+
+    // Token registrado (ausente para o token principal da config)
+    #[account(
+        seeds = [b"registered_token", registered_token.mint.as_ref()],
+        bump,
+    )]
+    pub registered_token: Option<Account<'info, RegisteredToken>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user_claim",
+            user.key().as_ref(),
+            registered_token.as_ref().map(|r| r.mint.as_ref()).unwrap_or(&[]),
+        ],
+        bump = user_claim_account.bump,
+    )]
+    pub user_claim_account: Account<'info, UserClaimAccount>,
+
+    // Exigida apenas para saltos acima de MAX_NONCE_RESET_JUMP
+    #[account(
+        mut,
+        seeds = [b"pending_action", admin.key().as_ref()],
+        bump = pending_action.bump,
+    )]
+    pub pending_action: Option<Account<'info, PendingAdminAction>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct AddToBlacklist<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"blacklist"],
+        bump = blacklist.bump,
+    )]
+    pub blacklist: Account<'info, BlacklistAccount>,
+
+    // Criada se o usuário nunca fez claim, para que a flag fique registrada
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + UserClaimAccount::INIT_SPACE,
+        seeds = [b"user_claim", user.key().as_ref()],
+        bump,
+    )]
+    pub user_claim_account: Account<'info, UserClaimAccount>,
+
+    // Exigida apenas para banimentos temporários (expires_at > 0)
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + BlacklistEntry::INIT_SPACE,
+        seeds = [b"blacklist_entry", user.key().as_ref()],
+        bump,
+    )]
+    pub blacklist_entry: Option<Account<'info, BlacklistEntry>>,
+
+    /// CHECK: Usuário a ser adicionado à blacklist
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageBlacklist<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"blacklist"],
+        bump = blacklist.bump,
+    )]
+    pub blacklist: Account<'info, BlacklistAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_claim", user.key().as_ref()],
+        bump = user_claim_account.bump,
+    )]
+    pub user_claim_account: Account<'info, UserClaimAccount>,
+
+    // Banimento temporário do usuário, encerrado antes do prazo se enviado
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"blacklist_entry", user.key().as_ref()],
+        bump = blacklist_entry.bump,
+    )]
+    pub blacklist_entry: Option<Account<'info, BlacklistEntry>>,
+
+    /// CHECK: Usuário a ser removido da blacklist
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct PruneBlacklistEntry<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [b"blacklist_entry", user.as_ref()],
+        bump = blacklist_entry.bump,
+    )]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+
+    // Recebe o rent da entrada; o admin pagou por ela em add_to_blacklist
+    #[account(
+        mut,
+        address = config.admin @ ErrorCode::Unauthorized,
+    )]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnban<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"blacklist"],
+        bump = blacklist.bump,
+    )]
+    pub blacklist: Account<'info, BlacklistAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_claim", user.key().as_ref()],
+        bump = user_claim_account.bump,
+    )]
+    pub user_claim_account: Account<'info, UserClaimAccount>,
+
+    #[account(
+        seeds = [b"blacklist_entry", user.key().as_ref()],
+        bump = blacklist_entry.bump,
+    )]
+    pub blacklist_entry: Option<Account<'info, BlacklistEntry>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SyncBlacklistFlag<'info> {
+    #[account(
+        seeds = [b"blacklist"],
+        bump = blacklist.bump,
+    )]
+    pub blacklist: Account<'info, BlacklistAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_claim", user.key().as_ref()],
+        bump = user_claim_account.bump,
+    )]
+    pub user_claim_account: Account<'info, UserClaimAccount>,
+
+    /// CHECK: Usuário cuja flag será sincronizada
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CheckBlacklist<'info> {
+    #[account(
+        seeds = [b"blacklist"],
+        bump = blacklist.bump,
+    )]
+    pub blacklist: Account<'info, BlacklistAccount>,
+
+    #[account(
+        seeds = [b"user_claim", user.key().as_ref()],
+        bump = user_claim_account.bump,
+    )]
+    pub user_claim_account: Option<Account<'info, UserClaimAccount>>,
+
+    #[account(
+        seeds = [b"blacklist_entry", user.key().as_ref()],
+        bump = blacklist_entry.bump,
+    )]
+    pub blacklist_entry: Option<Account<'info, BlacklistEntry>>,
+
+    /// CHECK: Usuário consultado
+    pub user: UncheckedAccount<'info>,
+
+    // Apenas para verbose_logging
+    #[account(
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyMintAuthority<'info> {
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    // Token registrado (ausente para o token principal da config)
+    #[account(
+        seeds = [b"registered_token", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub registered_token: Option<Account<'info, RegisteredToken>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.accepts_mint(&token_mint.key(), registered_token.as_deref())
+            @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct GetConfig<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterToken<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + RegisteredToken::INIT_SPACE,
+        seeds = [b"registered_token", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub registered_token: Account<'info, RegisteredToken>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRegisteredToken<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"registered_token", registered_token.mint.as_ref()],
+        bump,
+    )]
+    pub registered_token: Account<'info, RegisteredToken>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct UnregisterToken<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"registered_token", registered_token.mint.as_ref()],
+        bump,
+    )]
+    pub registered_token: Account<'info, RegisteredToken>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CloseConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pending_action", admin.key().as_ref()],
+        bump = pending_action.bump,
+    )]
+    pub pending_action: Option<Account<'info, PendingAdminAction>>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterUser<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserRegistration::INIT_SPACE,
+        seeds = [b"user_registration", user.key().as_ref()],
+        bump,
+    )]
+    pub user_registration: Account<'info, UserRegistration>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddToAllowlist<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Usuário a ser adicionado à allowlist
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + AllowlistEntry::INIT_SPACE,
+        seeds = [b"allowlist", user.key().as_ref()],
+        bump,
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromAllowlist<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Usuário a ser removido da allowlist
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"allowlist", user.key().as_ref()],
+        bump,
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct RequestAdminAction<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PendingAdminAction::INIT_SPACE,
+        seeds = [b"pending_action", admin.key().as_ref()],
+        bump,
+    )]
+    pub pending_action: Account<'info, PendingAdminAction>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminAction<'info> {
+    // Dono do PDA (também um admin anterior, para recuperar o rent)
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending_action", admin.key().as_ref()],
+        bump = pending_action.bump,
+    )]
+    pub pending_action: Account<'info, PendingAdminAction>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CheckAdminAction<'info> {
+    /// CHECK: Admin dono da ação pendente (qualquer um pode consultar)
+    pub admin: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"pending_action", admin.key().as_ref()],
+        bump = pending_action.bump,
+    )]
+    pub pending_action: Account<'info, PendingAdminAction>,
+
+    // Apenas para verbose_logging
+    #[account(
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAdminAction<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending_action", admin.key().as_ref()],
+        bump = pending_action.bump,
+        constraint = !pending_action.executed @ ErrorCode::InvalidInput,
+    )]
+    pub pending_action: Account<'info, PendingAdminAction>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintAuthority<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending_action", admin.key().as_ref()],
+        bump = pending_action.bump,
+        constraint = !pending_action.executed @ ErrorCode::InvalidInput,
+    )]
+    pub pending_action: Account<'info, PendingAdminAction>,
+
+    #[account(
+        mut,
+        constraint = token_mint.mint_authority == COption::Some(mint_authority.key())
+            @ ErrorCode::Unauthorized,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Mint authority configurada (o PDA ou a chave da rotação anterior)
+    #[account(
+        constraint = config.can_sign_mint(&mint_authority) @ ErrorCode::MintAuthorityMismatch,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CreateTokenMetadata<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = token_mint.mint_authority == COption::Some(mint_authority.key())
+            @ ErrorCode::Unauthorized,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Mint authority PDA (também update authority da metadata)
+    #[account(
+        seeds = [b"mint_authority"],
+        bump = config.mint_authority_bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Conta de metadata criada pelo programa Token Metadata
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: PDA dono do tesouro
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump,
+    )]
+    pub treasury_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = token_mint,
+        associated_token::authority = treasury_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositToTreasury<'info> {
+    pub depositor: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program,
+    )]
+    pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA dono do tesouro
+    #[account(
+        seeds = [b"treasury"],
+        bump = config.treasury_bump,
+    )]
+    pub treasury_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = treasury_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending_action", admin.key().as_ref()],
+        bump = pending_action.bump,
+        constraint = !pending_action.executed @ ErrorCode::InvalidInput,
+    )]
+    pub pending_action: Account<'info, PendingAdminAction>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: PDA dono do tesouro
+    #[account(
+        seeds = [b"treasury"],
+        bump = config.treasury_bump,
+    )]
+    pub treasury_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = treasury_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::token_program = token_program,
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SweepLamports<'info> {
+    pub admin: Signer<'info>,
+
+    /// CHECK: PDA do protocolo (config, stats, blacklist ou token registrado),
+    /// validado pelo discriminator no handler; só o excedente ao rent é movido
+    #[account(
+        mut,
+        owner = crate::ID @ ErrorCode::InvalidInput,
+    )]
+    pub source: UncheckedAccount<'info>,
+
+    /// CHECK: PDA canônico do tesouro (destino dos lamports)
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = config.treasury_bump,
+    )]
+    pub treasury_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryLamports<'info> {
+    pub admin: Signer<'info>,
+
+    /// CHECK: PDA canônico do tesouro (origem dos lamports)
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = config.treasury_bump,
+    )]
+    pub treasury_authority: UncheckedAccount<'info>,
+
+    // Carteira escolhida pelo admin
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Clawback<'info> {
+    pub admin: Signer<'info>,
+
+    /// CHECK: Usuário cujo claim está sendo revertido
+    pub user: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    // Token registrado (ausente para o token principal da config)
+    #[account(
+        mut,
+        seeds = [b"registered_token", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub registered_token: Option<Account<'info, RegisteredToken>>,
+
+    // Conta de contadores do claim revertido (por mint para tokens registrados)
+    #[account(
+        mut,
+        seeds = [
+            b"user_claim",
+            user.key().as_ref(),
+            registered_token.as_ref().map(|r| r.mint.as_ref()).unwrap_or(&[]),
+        ],
+        bump = user_claim_account.bump,
+    )]
+    pub user_claim_account: Account<'info, UserClaimAccount>,
+
+    /// CHECK: Carteira que recebeu o último claim (o próprio usuário ou outra)
+    #[account(address = user_claim_account.last_claim_recipient @ ErrorCode::InvalidTokenAccount)]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Freeze authority PDA (freeze authority e permanent delegate do mint)
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump,
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.accepts_mint(&token_mint.key(), registered_token.as_deref())
+            @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyPause<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAdminEmergency<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeThawTokenAccount<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = token_mint.freeze_authority == COption::Some(freeze_authority.key())
+            @ ErrorCode::FreezeAuthorityMismatch,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::token_program = token_program,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Freeze authority PDA (deve ser a freeze authority do mint)
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump,
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimRole<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Usuário que recebe o papel
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + ClaimRole::INIT_SPACE,
+        seeds = [b"claim_role", user.key().as_ref()],
+        bump,
+    )]
+    pub claim_role: Account<'info, ClaimRole>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeBlacklist<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + BlacklistAccount::INIT_SPACE,
+        seeds = [b"blacklist"],
+        bump,
+    )]
+    pub blacklist: Account<'info, BlacklistAccount>,
+
+    // Apenas a config canônica pode autorizar a criação da blacklist
+    #[account(
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeStats<'info> {
     #[account(mut)]
-    pub token_mint: Account<'info, Mint>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + StatsAccount::INIT_SPACE,
+        seeds = [b"stats"],
+        bump,
+    )]
+    pub stats: Account<'info, StatsAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, ConfigAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("The signature is invalid.")]
+    InvalidSignature,
+
+    #[msg("The signature is expired.")]
+    ExpiredSignature,
+
+    #[msg("Você não está autorizado a realizar esta ação")]
+    Unauthorized,
+
+    #[msg("Token de pagamento inválido")]
+    InvalidPaymentToken,
+
+    #[msg("Valor de pagamento inválido")]
+    InvalidPaymentAmount,
+
+    #[msg("Token de pagamento não configurado")]
+    PaymentTokenNotConfigured,
+
+    #[msg("Fundos insuficientes")]
+    InsufficientFunds,
+
+    #[msg("O sistema está pausado para emergência")]
+    SystemPaused,
+
+    #[msg("Valor de entrada inválido")]
+    InvalidInput,
+
+    #[msg("Erro de overflow matemático")]
+    MathOverflow,
+
+    #[msg("Conta de token inválida para este destinatário")]
+    InvalidTokenAccount,
+
+    #[msg("A assinatura tem timestamp no futuro")]
+    SignatureFromFuture,
+
+    #[msg("Delegate não autorizado para esta conta de token")]
+    InvalidDelegate,
+
+    #[msg("Quantidade delegada insuficiente")]
+    InsufficientDelegatedAmount,
+
+    #[msg("A freeze authority do mint não é o PDA do programa")]
+    FreezeAuthorityMismatch,
+
+    #[msg("Descrição muito longa (máximo de 256 bytes)")]
+    DescriptionTooLong,
+
+    #[msg("Usuário não está na allowlist")]
+    NotAllowlisted,
+
+    #[msg("A janela de clawback expirou")]
+    ClawbackWindowExpired,
+
+    #[msg("Lote excede o tamanho máximo permitido")]
+    BatchTooLarge,
+
+    #[msg("Motivo da pausa muito longo (máximo de 128 bytes)")]
+    PauseReasonTooLong,
+
+    #[msg("Registro do usuário ainda não atingiu a idade mínima")]
+    AccountTooYoung,
+
+    #[msg("Número de guardians acima do máximo permitido")]
+    TooManyGuardians,
+
+    #[msg("Assinaturas de guardians insuficientes")]
+    InsufficientGuardianSignatures,
+
+    #[msg("Voucher de claim expirado")]
+    VoucherExpired,
+
+    #[msg("Nome, símbolo ou URI da metadata inválidos")]
+    InvalidMetadata,
+
+    #[msg("Limite de claims nas últimas 24h atingido")]
+    ClaimHistoryFull,
+
+    #[msg("Categoria de claim inválida ou desativada")]
+    InvalidClaimCategory,
+
+    #[msg("Blacklist cheia")]
+    BlacklistFull,
+
+    #[msg("Quantidade acima do máximo por mint administrativo")]
+    AdminMintTooLarge,
+
+    #[msg("Nenhuma instrução ED25519 encontrada antes desta instrução")]
+    MissingEd25519Instruction,
+
+    #[msg("Já existe um claim deste usuário neste slot")]
+    SameSlotReplay,
+
+    #[msg("Prova merkle inválida para a raiz atual")]
+    InvalidMerkleProof,
+
+    #[msg("Remover a mint authority exige allow_disable = true")]
+    MintDisableNotConfirmed,
+
+    #[msg("O mint excederia o supply máximo do SPL Token (u64)")]
+    MintSupplyOverflow,
+
+    #[msg("Limite semanal de claims excedido")]
+    WeeklyLimitExceeded,
+
+    #[msg("A mint authority do token não é o PDA do programa")]
+    MintAuthorityMismatch,
+
+    #[msg("Claims do usuário suspensos temporariamente")]
+    UserSuspended,
+
+    #[msg("Escrow de vesting ainda bloqueado")]
+    EscrowLocked,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::{ed25519_program, entrypoint::ProgramResult};
+    use anchor_spl::token_interface::spl_token_2022;
+
+    #[test]
+    fn circuit_breaker_pauses_on_the_claim_past_the_threshold() {
+        let mut config = ConfigAccount {
+            claims_per_minute_threshold: 3,
+            ..Default::default()
+        };
+        let now = 1_000;
+
+        for _ in 0..3 {
+            assert!(!config.record_claim_for_circuit_breaker(now).unwrap());
+            assert!(!config.is_paused(now));
+        }
+        assert!(config.circuit_breaker_tripped(now));
+
+        // O claim seguinte na mesma janela pausa o sistema e não é contado
+        assert!(config.record_claim_for_circuit_breaker(now + 1).unwrap());
+        assert!(config.is_paused(now + 1));
+        assert_eq!(config.paused_at, now + 1);
+        assert_eq!(config.pause_expires_at, 0);
+        assert_eq!(config.claims_in_current_minute, 3);
+        config.emergency_paused = false;
+
+        // Uma nova janela volta a aceitar claims
+        assert!(!config.circuit_breaker_tripped(now + 60));
+        assert!(!config.record_claim_for_circuit_breaker(now + 60).unwrap());
+        assert_eq!(config.claims_in_current_minute, 1);
+    }
+
+    fn blacklist_entry_data(expires_at: i64) -> Vec<u8> {
+        let mut data = Vec::new();
+        BlacklistEntry {
+            user: Pubkey::new_unique(),
+            expires_at,
+            created_at: 0,
+            bump: 255,
+        }
+        .try_serialize(&mut data)
+        .unwrap();
+        data
+    }
+
+    #[test]
+    fn blacklist_entry_active_expired_and_pruned() {
+        let key = Pubkey::new_unique();
+        let owner = crate::ID;
+        let mut lamports = 1;
+        let mut data = blacklist_entry_data(1_000);
+        let entry = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        assert!(blacklist_entry_active(&entry, 999).unwrap());
+        // Vencida mas ainda não podada: não bloqueia
+        assert!(!blacklist_entry_active(&entry, 1_000).unwrap());
+
+        // Podada: a conta fechada não tem dados
+        let system_program = Pubkey::default();
+        let mut lamports = 0;
+        let mut empty: Vec<u8> = Vec::new();
+        let pruned =
+            AccountInfo::new(&key, false, false, &mut lamports, &mut empty, &system_program, false, 0);
+        assert!(!blacklist_entry_active(&pruned, 999).unwrap());
+    }
+
+    #[test]
+    fn circuit_breaker_disabled_with_zero_threshold() {
+        let mut config = ConfigAccount::default();
+        for _ in 0..1_000 {
+            assert!(!config.record_claim_for_circuit_breaker(0).unwrap());
+        }
+        assert!(!config.circuit_breaker_tripped(0));
+        assert_eq!(config.claims_in_current_minute, 0);
+    }
+
+    // Gerador pseudoaleatório determinístico (xorshift) para testes de propriedade
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    #[test]
+    fn split_bps_never_exceeds_amount() {
+        let mut state = 0x9E37_79B9_7F4A_7C15;
+        for _ in 0..10_000 {
+            let amount = xorshift(&mut state);
+            let bps = xorshift(&mut state) % (BPS_DENOMINATOR + 1);
+
+            let (part, rest) = split_bps(amount, bps).unwrap();
+            assert_eq!(part as u128 + rest as u128, amount as u128);
+            // Floor: a parte nunca passa da fração exata
+            assert!(part as u128 * BPS_DENOMINATOR as u128 <= amount as u128 * bps as u128);
+            assert_eq!(part, apply_bps(amount, bps).unwrap());
+        }
+    }
+
+    #[test]
+    fn apply_bps_rounds_down_and_rejects_invalid_splits() {
+        assert_eq!(apply_bps(9_999, 1).unwrap(), 0);
+        assert_eq!(apply_bps(10_001, 1).unwrap(), 1);
+        assert_eq!(apply_bps(u64::MAX, BPS_DENOMINATOR).unwrap(), u64::MAX);
+        assert_eq!(split_bps(7, 0).unwrap(), (0, 7));
+        assert_eq!(split_bps(7, BPS_DENOMINATOR).unwrap(), (7, 0));
+
+        assert_eq!(
+            split_bps(1, BPS_DENOMINATOR + 1).unwrap_err(),
+            error!(ErrorCode::InvalidInput)
+        );
+        assert_eq!(
+            apply_bps(u64::MAX, BPS_DENOMINATOR + 1).unwrap_err(),
+            error!(ErrorCode::MathOverflow)
+        );
+    }
+
+    #[test]
+    fn admin_action_type_discriminants_are_stable() {
+        let pinned = [
+            (AdminActionType::ChangeAdmin, 0u8),
+            (AdminActionType::ChangeToken, 1),
+            (AdminActionType::EmergencyWithdraw, 2),
+            (AdminActionType::ChangeMintAuthority, 3),
+            (AdminActionType::SetAdminMintCap, 4),
+            (AdminActionType::CloseConfig, 5),
+            (AdminActionType::ChangeMaxClaim, 6),
+            (AdminActionType::ChangeSupplyLimit, 7),
+            (AdminActionType::SetUserLimit, 8),
+            (AdminActionType::ResetUserNonce, 9),
+        ];
+
+        for (action, discriminant) in pinned {
+            let bytes = action.try_to_vec().unwrap();
+            assert_eq!(bytes, vec![discriminant]);
+            assert_eq!(bytes.len(), AdminActionType::INIT_SPACE);
+            assert_eq!(AdminActionType::try_from_slice(&bytes).unwrap(), action);
+        }
+
+        // Discriminante desconhecido não pode virar uma ação válida
+        assert!(AdminActionType::try_from_slice(&[pinned.len() as u8]).is_err());
+    }
+
+    #[test]
+    fn init_space_matches_fully_populated_accounts() {
+        let config = ConfigAccount {
+            pause_reason: "x".repeat(MAX_PAUSE_REASON_LEN),
+            guardians: vec![Pubkey::new_unique(); MAX_GUARDIANS],
+            ..Default::default()
+        };
+        assert_eq!(config.try_to_vec().unwrap().len(), ConfigAccount::INIT_SPACE);
+
+        let entry = ClaimEntry { timestamp: 1, amount: 1, category: 1 };
+        let user_claim = UserClaimAccount {
+            user: Pubkey::new_unique(),
+            total_claimed: 1,
+            last_claim_timestamp: 1,
+            daily_claimed: 1,
+            hourly_claimed: 1,
+            claim_nonce: 1,
+            is_blacklisted: true,
+            last_claim_amount: 1,
+            initialized: true,
+            claim_history: vec![entry; MAX_CLAIM_HISTORY],
+            custom_daily_limit: Some(1),
+            last_claim_slot: 1,
+            bump: 255,
+            weekly_claimed: 1,
+            weekly_reset_timestamp: 1,
+            appeal_requested: true,
+            appeal_timestamp: 1,
+            suspended_until: 1,
+            last_claim_mint: Pubkey::new_unique(),
+            last_claim_recipient: Pubkey::new_unique(),
+        };
+        assert_eq!(user_claim.try_to_vec().unwrap().len(), UserClaimAccount::INIT_SPACE);
+
+        let blacklist = BlacklistAccount {
+            admin: Pubkey::new_unique(),
+            blacklisted_users: vec![Pubkey::new_unique(); MAX_BLACKLIST_SIZE],
+            bump: 255,
+        };
+        assert_eq!(blacklist.try_to_vec().unwrap().len(), BlacklistAccount::INIT_SPACE);
+
+        let pending = PendingAdminAction {
+            action_type: AdminActionType::ResetUserNonce,
+            new_value: Pubkey::new_unique(),
+            new_amount: 1,
+            requested_at: 1,
+            executed: true,
+            bump: 255,
+            ready_at: 1,
+        };
+        assert_eq!(pending.try_to_vec().unwrap().len(), PendingAdminAction::INIT_SPACE);
+    }
+
+    #[test]
+    fn hourly_cap_allows_claims_for_small_daily_limits() {
+        let config = ConfigAccount {
+            emission: EmissionConfig { initial_rate: BPS_DENOMINATOR, ..Default::default() },
+            ..Default::default()
+        };
+
+        for max_claim_per_user in 1..=24u64 {
+            let (daily, hourly) = claim_limits(&config, max_claim_per_user, None, 0).unwrap();
+            assert_eq!(daily, max_claim_per_user);
+            // Pelo menos uma unidade base por hora, sem passar do diário
+            assert_eq!(hourly, 1);
+        }
+
+        let (daily, hourly) = claim_limits(&config, 25, None, 0).unwrap();
+        assert_eq!((daily, hourly), (25, 2));
+
+        // O limite diário absoluto do admin segue a mesma regra
+        for limit in 1..=24u64 {
+            let (daily, hourly) =
+                category_claim_limits(&config, 0, None, Some(limit), DEFAULT_CLAIM_CATEGORY, 0)
+                    .unwrap();
+            assert_eq!((daily, hourly), (limit, 1));
+        }
+    }
+
+    #[test]
+    fn signature_timestamp_direction() {
+        let user = Pubkey::new_unique();
+        let now = 1_000_000;
+
+        // +60s: rejeitado por padrão, aceito com allow_future
+        assert_eq!(
+            validate_signature_timestamp(now, now + 60, false, &user).unwrap_err(),
+            error!(ErrorCode::SignatureFromFuture)
+        );
+        validate_signature_timestamp(now, now + 60, true, &user).unwrap();
+
+        // Timestamps no passado valem até a tolerância, em qualquer modo
+        validate_signature_timestamp(now, now, false, &user).unwrap();
+        validate_signature_timestamp(now, now - SIGNATURE_TOLERANCE_SECONDS, false, &user).unwrap();
+        assert_eq!(
+            validate_signature_timestamp(now, now - SIGNATURE_TOLERANCE_SECONDS - 1, true, &user)
+                .unwrap_err(),
+            error!(ErrorCode::ExpiredSignature)
+        );
+
+        // Mesmo com allow_future, o futuro é limitado pela tolerância
+        assert_eq!(
+            validate_signature_timestamp(now, now + SIGNATURE_TOLERANCE_SECONDS + 1, true, &user)
+                .unwrap_err(),
+            error!(ErrorCode::ExpiredSignature)
+        );
+    }
+
+    #[test]
+    fn emission_halvings_scale_the_claim_cap() {
+        let day = ONE_DAY_SECONDS;
+        let emission = EmissionConfig {
+            initial_rate: BPS_DENOMINATOR,
+            halving_interval_seconds: 30 * day,
+            genesis_ts: 1_000,
+        };
+        let base = 1_000_000;
+
+        // Antes e no genesis: sem halving
+        assert_eq!(effective_max_claim(base, &emission, 0).unwrap(), base);
+        assert_eq!(effective_max_claim(base, &emission, 1_000).unwrap(), base);
+        assert_eq!(effective_max_claim(base, &emission, 1_000 + 30 * day - 1).unwrap(), base);
+
+        // Um halving
+        assert_eq!(effective_max_claim(base, &emission, 1_000 + 30 * day).unwrap(), base / 2);
+
+        // Vários halvings
+        assert_eq!(effective_max_claim(base, &emission, 1_000 + 3 * 30 * day).unwrap(), base / 8);
+        assert_eq!(effective_max_claim(base, &emission, 1_000 + 10 * 30 * day).unwrap(), base >> 10);
+        assert_eq!(effective_max_claim(base, &emission, 1_000 + 200 * 30 * day).unwrap(), 0);
+
+        // initial_rate escala o limite; intervalo 0 desativa o halving
+        let flat = EmissionConfig {
+            initial_rate: BPS_DENOMINATOR / 2,
+            halving_interval_seconds: 0,
+            genesis_ts: 0,
+        };
+        assert_eq!(effective_max_claim(base, &flat, i64::MAX).unwrap(), base / 2);
+    }
+
+    const SIGNER: Pubkey = Pubkey::new_from_array([9u8; 32]);
+
+    // Dados de uma instrução ED25519 com uma assinatura, tudo na própria
+    // instrução: [header | offsets | assinatura | pubkey | mensagem]
+    fn ed25519_ix_data(message: &[u8], signature: &[u8; 64], public_key: &Pubkey) -> Vec<u8> {
+        let sig_offset = (ED25519_SIGNATURE_OFFSETS_START + ED25519_SIGNATURE_OFFSETS_SIZE) as u16;
+        let pk_offset = sig_offset + 64;
+        let msg_offset = pk_offset + 32;
+
+        let mut data = vec![1u8, 0];
+        for field in [
+            sig_offset,
+            u16::MAX,
+            pk_offset,
+            u16::MAX,
+            msg_offset,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(signature);
+        data.extend_from_slice(public_key.as_ref());
+        data.extend_from_slice(message);
+        data
+    }
+
+    // Conta do sysvar de instruções com `instructions` e a instrução atual em `current`
+    fn instructions_sysvar_data(instructions: &[(Pubkey, Vec<u8>)], current: u16) -> Vec<u8> {
+        use anchor_lang::solana_program::sysvar::instructions::{
+            construct_instructions_data, store_current_index, BorrowedInstruction,
+        };
+
+        let borrowed: Vec<BorrowedInstruction> = instructions
+            .iter()
+            .map(|(program_id, data)| BorrowedInstruction {
+                program_id,
+                accounts: Vec::new(),
+                data,
+            })
+            .collect();
+        let mut data = construct_instructions_data(&borrowed);
+        store_current_index(&mut data, current);
+        data
+    }
+
+    fn verify_with(instructions: &[(Pubkey, Vec<u8>)], current: u16) -> Result<()> {
+        let key = sysvar_instructions::ID;
+        let owner = anchor_lang::solana_program::sysvar::ID;
+        let mut lamports = 1;
+        let mut data = instructions_sysvar_data(instructions, current);
+        let sysvar = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        verify_signature(&sysvar, b"claim", &[7u8; 64], &SIGNER, &Pubkey::default())
+    }
+
+    #[test]
+    fn verify_signature_scans_for_the_matching_ed25519_instruction() {
+        use anchor_lang::solana_program::{ed25519_program, system_program as system};
+
+        let ours = (crate::ID, Vec::new());
+        let matching = (ed25519_program::ID, ed25519_ix_data(b"claim", &[7u8; 64], &SIGNER));
+        let other_sig = (ed25519_program::ID, ed25519_ix_data(b"outro", &[7u8; 64], &SIGNER));
+        let unrelated = (system::ID, vec![2, 0, 0, 0]);
+
+        // ED25519 no índice 0, nossa instrução no 1
+        verify_with(&[matching.clone(), ours.clone()], 1).unwrap();
+
+        // Instrução não relacionada antes da ED25519
+        verify_with(&[unrelated.clone(), matching.clone(), ours.clone()], 2).unwrap();
+
+        // Várias ED25519: vale a que confere, não a imediatamente anterior
+        verify_with(&[matching.clone(), other_sig.clone(), ours.clone()], 2).unwrap();
+
+        // Só instruções posteriores à atual não contam
+        assert_eq!(
+            verify_with(&[ours.clone(), matching], 0).unwrap_err(),
+            error!(ErrorCode::MissingEd25519Instruction)
+        );
+        assert_eq!(
+            verify_with(&[unrelated, other_sig, ours], 2).unwrap_err(),
+            error!(ErrorCode::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn ed25519_payload_rejects_foreign_or_truncated_data() {
+        let signature = [7u8; 64];
+        let data = ed25519_ix_data(b"claim", &signature, &SIGNER);
+        assert!(ed25519_payload_matches(&data, b"claim", &signature, &SIGNER));
+        assert!(!ed25519_payload_matches(&data, b"claim", &[8u8; 64], &SIGNER));
+        assert!(!ed25519_payload_matches(&data, b"claim", &signature, &Pubkey::new_unique()));
+
+        // Dados apontando para outra instrução (instruction_index != u16::MAX)
+        let mut foreign = data.clone();
+        foreign[ED25519_SIGNATURE_OFFSETS_START + 2..ED25519_SIGNATURE_OFFSETS_START + 4]
+            .copy_from_slice(&0u16.to_le_bytes());
+        assert!(!ed25519_payload_matches(&foreign, b"claim", &signature, &SIGNER));
+
+        // Dados truncados ou vazios
+        assert!(!ed25519_payload_matches(&data[..data.len() - 1], b"claim", &signature, &SIGNER));
+        assert!(!ed25519_payload_matches(&data[..8], b"claim", &signature, &SIGNER));
+        assert!(!ed25519_payload_matches(&[], b"claim", &signature, &SIGNER));
+    }
+
+    fn merkle_leaf(user: &Pubkey) -> [u8; 32] {
+        keccak::hashv(&[user.as_ref()]).0
+    }
+
+    fn merkle_parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[&a, &b]).0
+        } else {
+            keccak::hashv(&[&b, &a]).0
+        }
+    }
+
+    #[test]
+    fn merkle_allowlist_proofs() {
+        let users: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = users.iter().map(merkle_leaf).collect();
+        let left = merkle_parent(leaves[0], leaves[1]);
+        let right = merkle_parent(leaves[2], leaves[3]);
+        let root = merkle_parent(left, right);
+
+        // Prova válida para cada folha
+        assert!(verify_merkle_proof(leaves[0], &[leaves[1], right], &root));
+        assert!(verify_merkle_proof(leaves[3], &[leaves[2], left], &root));
+
+        // Prova inválida: carteira fora da árvore ou irmão errado
+        let outsider = merkle_leaf(&Pubkey::new_unique());
+        assert!(!verify_merkle_proof(outsider, &[leaves[1], right], &root));
+        assert!(!verify_merkle_proof(leaves[0], &[leaves[2], right], &root));
+
+        // Raiz antiga: depois que o admin troca a raiz, a prova antiga não vale
+        let new_right = merkle_parent(leaves[2], merkle_leaf(&Pubkey::new_unique()));
+        let new_root = merkle_parent(left, new_right);
+        assert!(!verify_merkle_proof(leaves[0], &[leaves[1], right], &new_root));
+        assert!(verify_merkle_proof(leaves[0], &[leaves[1], new_right], &new_root));
+
+        // Provas acima da profundidade máxima são rejeitadas
+        let deep = vec![leaves[1]; MAX_MERKLE_PROOF_LEN + 1];
+        assert!(!verify_merkle_proof(leaves[0], &deep, &root));
+    }
+
+    fn registered_token(mint: Pubkey, max_claim_per_user: u64) -> RegisteredToken {
+        RegisteredToken {
+            mint,
+            supply_limit: 1_000_000,
+            total_minted: 0,
+            max_claim_per_user,
+        }
+    }
+
+    #[test]
+    fn claims_accept_the_second_of_two_registered_mints() {
+        let config = ConfigAccount {
+            payment_token_mint: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let first = registered_token(Pubkey::new_unique(), 100);
+        let second = registered_token(Pubkey::new_unique(), 100);
+
+        assert!(config.accepts_mint(&config.payment_token_mint, None));
+        assert!(config.accepts_mint(&first.mint, Some(&first)));
+        assert!(config.accepts_mint(&second.mint, Some(&second)));
+
+        // Mint sem registro, ou com o registro de outro mint, é rejeitado
+        assert!(!config.accepts_mint(&Pubkey::new_unique(), None));
+        assert!(!config.accepts_mint(&second.mint, None));
+        assert!(!config.accepts_mint(&second.mint, Some(&first)));
+    }
+
+    #[test]
+    fn registered_mints_carry_their_own_claim_caps() {
+        let config = ConfigAccount {
+            max_claim_per_user: 1_000,
+            total_supply_limit: 5_000,
+            emission: EmissionConfig { initial_rate: BPS_DENOMINATOR, ..Default::default() },
+            ..Default::default()
+        };
+        let common = registered_token(Pubkey::new_unique(), 480);
+        let rare = registered_token(Pubkey::new_unique(), 24);
+
+        let (_, _, common_cap) = config.mint_limits(Some(&common));
+        let (_, _, rare_cap) = config.mint_limits(Some(&rare));
+        assert_eq!(claim_limits(&config, common_cap, None, 0).unwrap(), (480, 20));
+        assert_eq!(claim_limits(&config, rare_cap, None, 0).unwrap(), (24, 1));
+
+        // Sem registro (token principal) valem os limites da config
+        assert_eq!(config.mint_limits(None), (0, 5_000, 1_000));
+        assert_eq!(config.mint_limits(Some(&rare)), (0, 1_000_000, 24));
+    }
+
+    #[test]
+    fn mints_follow_the_rotated_mint_authority() {
+        let (pda, bump) = Pubkey::find_program_address(&[b"mint_authority"], &crate::ID);
+        let mut config = ConfigAccount {
+            mint_authority: pda,
+            mint_authority_bump: bump,
+            ..Default::default()
+        };
+        let owner = Pubkey::default();
+        let new_authority = Pubkey::new_unique();
+        let (mut l1, mut l2, mut l3) = (0, 0, 0);
+        let (mut d1, mut d2, mut d3) = (Vec::new(), Vec::new(), Vec::new());
+        let pda_info = AccountInfo::new(&pda, false, false, &mut l1, &mut d1, &owner, false, 0);
+        let unsigned = AccountInfo::new(&new_authority, false, false, &mut l2, &mut d2, &owner, false, 0);
+        let signed = AccountInfo::new(&new_authority, true, false, &mut l3, &mut d3, &owner, false, 0);
+
+        // O PDA do programa assina via seeds
+        assert!(config.can_sign_mint(&pda_info));
+        assert!(!config.can_sign_mint(&signed));
+
+        // Depois de set_mint_authority, claims e mints usam a nova authority,
+        // que precisa assinar; o PDA antigo não vale mais
+        config.mint_authority = new_authority;
+        assert!(config.can_sign_mint(&signed));
+        assert!(!config.can_sign_mint(&unsigned));
+        assert!(!config.can_sign_mint(&pda_info));
+
+        // De volta ao PDA, sem assinatura
+        config.mint_authority = pda;
+        assert!(config.can_sign_mint(&pda_info));
+    }
+
+    #[test]
+    fn claimed_session_replay_and_retention() {
+        // O init falha no replay porque a mesma sessão deriva o mesmo PDA,
+        // com qualquer quantidade ou nonce; sessões novas têm PDA próprio
+        let session = [7u8; 32];
+        let pda = |hash: &[u8; 32]| {
+            Pubkey::find_program_address(&[b"claimed_session", hash.as_ref()], &crate::ID).0
+        };
+        assert_eq!(pda(&session), pda(&session));
+        assert_ne!(pda(&session), pda(&[8u8; 32]));
+
+        let claimed = ClaimedSession {
+            claimer: Pubkey::new_unique(),
+            session_hash: session,
+            claimed_at: 1_000,
+            bump: 255,
+        };
+        assert_eq!(claimed.try_to_vec().unwrap().len(), ClaimedSession::INIT_SPACE);
+        assert!(!claimed.closable(1_000 + SESSION_RETENTION_SECONDS - 1));
+        assert!(claimed.closable(1_000 + SESSION_RETENTION_SECONDS));
+    }
+
+    #[test]
+    fn stats_count_unique_claimers_once_per_user() {
+        let mut stats = StatsAccount {
+            unique_claimers: 0,
+            lifetime_claims: 0,
+            lifetime_burns: 0,
+            lifetime_mints: 0,
+        };
+        // Primeiro e segundo claim do mesmo usuário na conta base
+        stats.record_claim(true).unwrap();
+        stats.record_claim(false).unwrap();
+        // Primeiro claim do mesmo usuário num mint registrado não é usuário novo
+        stats.record_claim(false).unwrap();
+        assert_eq!(stats.lifetime_claims, 3);
+        assert_eq!(stats.unique_claimers, 1);
+
+        stats.record_burn().unwrap();
+        stats.record_mint().unwrap();
+        assert_eq!((stats.lifetime_burns, stats.lifetime_mints), (1, 1));
+
+        stats.lifetime_claims = u64::MAX;
+        assert_eq!(stats.record_claim(false).unwrap_err(), error!(ErrorCode::MathOverflow));
+    }
+
+    #[test]
+    fn reset_user_limits_clears_only_the_category_windows() {
+        let now = 10 * ONE_DAY_SECONDS;
+        let config = ConfigAccount {
+            max_claim_per_user: 1_000,
+            emission: EmissionConfig { initial_rate: BPS_DENOMINATOR, ..Default::default() },
+            ..Default::default()
+        };
+        let (max_daily, _) =
+            category_claim_limits(&config, 1_000, None, None, DEFAULT_CLAIM_CATEGORY, now).unwrap();
+        let mut user_claim = UserClaimAccount {
+            daily_claimed: max_daily,
+            hourly_claimed: 10,
+            weekly_claimed: max_daily + 7,
+            weekly_reset_timestamp: now - ONE_DAY_SECONDS,
+            claim_history: vec![
+                ClaimEntry { timestamp: now - 60, amount: max_daily, category: DEFAULT_CLAIM_CATEGORY },
+                ClaimEntry { timestamp: now - 30, amount: 7, category: 1 },
+            ],
+            ..Default::default()
+        };
+        // No limite: o próximo claim passaria do teto diário
+        let daily = user_claim.claimed_within(now, ONE_DAY_SECONDS, DEFAULT_CLAIM_CATEGORY).unwrap();
+        assert_eq!(daily, max_daily);
+
+        user_claim.reset_category_window(DEFAULT_CLAIM_CATEGORY);
+        let daily = user_claim.claimed_within(now, ONE_DAY_SECONDS, DEFAULT_CLAIM_CATEGORY).unwrap();
+        assert!(daily < max_daily);
+        assert_eq!((user_claim.daily_claimed, user_claim.hourly_claimed), (0, 0));
+        // Outras categorias e a janela semanal ficam intactas
+        assert_eq!(user_claim.claimed_within(now, ONE_DAY_SECONDS, 1).unwrap(), 7);
+        assert_eq!(user_claim.claimed_this_week(now), max_daily + 7);
+
+        user_claim.reset_category_window(1);
+        assert!(user_claim.claim_history.is_empty());
+    }
+
+    #[test]
+    fn claim_burn_tax_mints_only_the_net_supply() {
+        // 0 bps: nada a queimar, o destino recebe o claim inteiro
+        let (burn_amount, net_amount) = split_bps(1_000, 0).unwrap();
+        assert_eq!((burn_amount, net_amount), (0, 1_000));
+        for to_other_wallet in [false, true] {
+            assert_eq!(claim_mint_amounts(1_000, burn_amount, 0, to_other_wallet).unwrap(), (1_000, 0));
+        }
+
+        // 500 bps: o destinatário fica com 950 e o supply cresce 950
+        let (burn_amount, net_amount) = split_bps(1_000, 500).unwrap();
+        assert_eq!((burn_amount, net_amount), (50, 950));
+        let (minted, burned) = claim_mint_amounts(1_000, burn_amount, 0, false).unwrap();
+        assert_eq!((minted, burned), (1_000, 50));
+        assert_eq!(minted - burned, net_amount);
+        // Para outra carteira só o líquido é mintado, sem mint + queima da taxa
+        assert_eq!(claim_mint_amounts(1_000, burn_amount, 0, true).unwrap(), (950, 0));
+
+        // A parte em escrow sai do que é mintado agora
+        assert_eq!(claim_mint_amounts(1_000, burn_amount, 200, false).unwrap(), (800, 50));
+        assert_eq!(claim_mint_amounts(1_000, burn_amount, 200, true).unwrap(), (750, 0));
+    }
+
+    #[cfg(all(test, feature = "structured-logs"))]
+    #[test]
+    fn structured_log_line_is_compact_key_value() {
+        let user = Pubkey::new_unique();
+        let line = structured_log_line(
+            "claim",
+            &[("user", &user), ("amount", &1_500u64), ("burn", &0u64), ("seq", &7u64)],
+        );
+        assert_eq!(line, format!("op=claim user={} amount=1500 burn=0 seq=7", user));
+        assert!(line.is_ascii());
+        assert_eq!(structured_log_line("batch_mint", &[]), "op=batch_mint");
+    }
+
+    // Runtime mínimo para executar instruções fora da cadeia: clock, rent,
+    // eventos capturados e CPIs despachados para os token programs. O estado
+    // é por thread, então testes em paralelo não se misturam
+    mod runtime {
+        use super::*;
+        use anchor_lang::solana_program::{
+            entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS},
+            instruction::Instruction,
+            program_error::UNSUPPORTED_SYSVAR,
+            program_pack::Pack,
+            program_stubs::{set_syscall_stubs, SyscallStubs},
+        };
+        use anchor_lang::InstructionData;
+        use anchor_spl::associated_token::spl_associated_token_account;
+        use anchor_spl::token::spl_token;
+        use anchor_spl::token_2022::spl_token_2022;
+        use std::cell::{Cell, RefCell};
+        use std::collections::HashMap;
+        use std::sync::Once;
+
+        // Contas da instrução em execução, vazadas por `Bank::execute`
+        type Caller = &'static [AccountInfo<'static>];
+        type CpiHook = Box<dyn FnOnce(Caller)>;
+        // Processador de um programa externo emulado (ver `Bank::load`)
+        pub type Stub = fn(&[AccountInfo], &[u8]) -> ProgramResult;
+
+        thread_local! {
+            static NOW: Cell<Option<i64>> = const { Cell::new(None) };
+            static REMAINING_CU: Cell<u64> = const { Cell::new(200_000) };
+            static EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+            static LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+            static CALLER: Cell<Option<Caller>> = const { Cell::new(None) };
+            static CPI_HOOK: RefCell<Option<CpiHook>> = const { RefCell::new(None) };
+            // Programas na pilha de CPIs; o do topo assina com as suas seeds
+            static PROGRAMS: RefCell<Vec<Pubkey>> = const { RefCell::new(Vec::new()) };
+            static RETURN_DATA: RefCell<Option<(Pubkey, Vec<u8>)>> = const { RefCell::new(None) };
+            static STUBS: RefCell<Vec<(Pubkey, Stub)>> = const { RefCell::new(Vec::new()) };
+        }
+
+        static INSTALL: Once = Once::new();
+
+        struct TestStubs;
+
+        impl SyscallStubs for TestStubs {
+            fn sol_remaining_compute_units(&self) -> u64 {
+                REMAINING_CU.with(|remaining| {
+                    let value = remaining.get();
+                    remaining.set(value.saturating_sub(1_000));
+                    value
+                })
+            }
+
+            fn sol_log(&self, message: &str) {
+                LOGS.with(|logs| logs.borrow_mut().push(message.to_string()));
+            }
+
+            fn sol_log_data(&self, fields: &[&[u8]]) {
+                EVENTS.with(|events| events.borrow_mut().extend(fields.iter().map(|f| f.to_vec())));
+            }
+
+            // Sem `start` na thread o clock continua indisponível, como no stub padrão
+            fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+                match NOW.get() {
+                    Some(now) => {
+                        let clock = Clock { slot: now as u64, unix_timestamp: now, ..Clock::default() };
+                        unsafe { *(var_addr as *mut Clock) = clock };
+                        SUCCESS
+                    }
+                    None => UNSUPPORTED_SYSVAR,
+                }
+            }
+
+            // Return data do programa em execução (ex. get_account_data_size
+            // lido pelo programa de ATA)
+            fn sol_set_return_data(&self, data: &[u8]) {
+                let program = PROGRAMS.with(|programs| programs.borrow().last().copied()).unwrap_or(crate::ID);
+                RETURN_DATA.set(Some((program, data.to_vec())));
+            }
+
+            fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+                RETURN_DATA.take()
+            }
+
+            fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+                unsafe { *(var_addr as *mut Rent) = Rent::default() };
+                SUCCESS
+            }
+
+            // PDAs só assinam com as seeds do programa chamador, como no runtime
+            fn sol_invoke_signed(
+                &self,
+                instruction: &Instruction,
+                account_infos: &[AccountInfo],
+                signers_seeds: &[&[&[u8]]],
+            ) -> ProgramResult {
+                let caller = PROGRAMS.with(|programs| programs.borrow().last().copied()).unwrap_or(crate::ID);
+                let signed_by_program = |key: &Pubkey| {
+                    signers_seeds
+                        .iter()
+                        .any(|seeds| Pubkey::create_program_address(seeds, &caller).as_ref() == Ok(key))
+                };
+                if let (Some(hook), Some(caller)) = (CPI_HOOK.take(), CALLER.get()) {
+                    hook(caller);
+                }
+                let accounts = instruction
+                    .accounts
+                    .iter()
+                    .map(|meta| {
+                        let mut info = account_infos
+                            .iter()
+                            .find(|info| *info.key == meta.pubkey)
+                            .ok_or(ProgramError::NotEnoughAccountKeys)?
+                            .clone();
+                        if meta.is_signer && !info.is_signer && !signed_by_program(info.key) {
+                            return Err(ProgramError::MissingRequiredSignature);
+                        }
+                        info.is_signer = meta.is_signer;
+                        info.is_writable = meta.is_writable;
+                        Ok(info)
+                    })
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+
+                dispatch(&instruction.program_id, &accounts, &instruction.data)
+            }
+        }
+
+        // Executar um dos programas nativos/SPL carregados (o programa sob teste
+        // só roda pelo entrypoint, em `Bank::process`)
+        fn dispatch(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+            PROGRAMS.with(|programs| programs.borrow_mut().push(*program_id));
+            let result = if *program_id == spl_token_2022::ID {
+                spl_token_2022::processor::Processor::process(program_id, accounts, data)
+            } else if *program_id == spl_token::ID {
+                spl_token::processor::Processor::process(program_id, accounts, data)
+            } else if *program_id == system_program::ID {
+                process_system(data, accounts)
+            } else if *program_id == anchor_spl::associated_token::ID {
+                spl_associated_token_account::processor::process_instruction(program_id, accounts, data)
+            } else if let Some(stub) = STUBS.with(|stubs| {
+                stubs.borrow().iter().find(|(id, _)| id == program_id).map(|(_, stub)| *stub)
+            }) {
+                stub(accounts, data)
+            } else {
+                Err(ProgramError::IncorrectProgramId)
+            };
+            PROGRAMS.with(|programs| programs.borrow_mut().pop());
+            result
+        }
+
+        // System Program mínimo para os `init` do Anchor: criar, alocar,
+        // atribuir e transferir lamports. Alocar troca o buffer (vazado) da conta
+        fn process_system(data: &[u8], accounts: &[AccountInfo]) -> ProgramResult {
+            use anchor_lang::solana_program::{
+                program_utils::limited_deserialize, system_instruction::SystemInstruction,
+            };
+
+            fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+                let balance = from.lamports().checked_sub(lamports).ok_or(ProgramError::InsufficientFunds)?;
+                **from.try_borrow_mut_lamports()? = balance;
+                **to.try_borrow_mut_lamports()? += lamports;
+                Ok(())
+            }
+
+            fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+                if !account.data_is_empty() || *account.owner != system_program::ID {
+                    return Err(ProgramError::AccountAlreadyInitialized);
+                }
+                *account.try_borrow_mut_data()? = serialized_data(&vec![0; space as usize]);
+                Ok(())
+            }
+
+            fn assign(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+                if *account.owner != system_program::ID {
+                    return Err(ProgramError::IllegalOwner);
+                }
+                account.assign(owner);
+                Ok(())
+            }
+
+            let instruction = limited_deserialize::<SystemInstruction>(data, 1_232)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            match instruction {
+                SystemInstruction::CreateAccount { lamports, space, owner } => {
+                    transfer(&accounts[0], &accounts[1], lamports)?;
+                    allocate(&accounts[1], space)?;
+                    assign(&accounts[1], &owner)
+                }
+                SystemInstruction::Transfer { lamports } => transfer(&accounts[0], &accounts[1], lamports),
+                SystemInstruction::Allocate { space } => allocate(&accounts[0], space),
+                SystemInstruction::Assign { owner } => assign(&accounts[0], &owner),
+                _ => Err(ProgramError::InvalidInstructionData),
+            }
+        }
+
+        // Chave e dados vazados na disposição da entrada serializada pelo
+        // runtime, que AccountInfo::realloc (ex. `close` do Anchor) assume: o
+        // tamanho original logo antes da chave, o tamanho atual logo antes dos
+        // dados e MAX_PERMITTED_DATA_INCREASE de folga depois deles
+        #[repr(C)]
+        struct SerializedKey {
+            flags: [u8; 4],
+            original_data_len: u32,
+            key: Pubkey,
+        }
+
+        fn serialized_key(key: Pubkey, original_data_len: usize) -> &'static Pubkey {
+            let header = SerializedKey { flags: [0; 4], original_data_len: original_data_len as u32, key };
+            &Box::leak(Box::new(header)).key
+        }
+
+        fn serialized_data(data: &[u8]) -> &'static mut [u8] {
+            let words = vec![0u64; 1 + (data.len() + MAX_PERMITTED_DATA_INCREASE).div_ceil(8)].leak();
+            words[0] = data.len() as u64;
+            let bytes = unsafe { std::slice::from_raw_parts_mut(words[1..].as_mut_ptr() as *mut u8, data.len()) };
+            bytes.copy_from_slice(data);
+            bytes
+        }
+
+        // Ativar os stubs nesta thread com o relógio em `now`
+        pub fn start(now: i64) {
+            INSTALL.call_once(|| {
+                set_syscall_stubs(Box::new(TestStubs));
+            });
+            NOW.set(Some(now));
+            EVENTS.take();
+            LOGS.take();
+            STUBS.take();
+        }
+
+        pub fn set_now(now: i64) {
+            NOW.set(Some(now));
+        }
+
+        // Rodar `hook` uma vez, no próximo CPI e antes do token program, com as
+        // contas da instrução em execução, como um transfer hook do Token-2022
+        // que tenta reentrar no programa (o runtime real bloqueia A -> B -> A;
+        // isto simula o pior caso)
+        pub fn on_cpi(hook: impl FnOnce(Caller) + 'static) {
+            CPI_HOOK.set(Some(Box::new(hook)));
+        }
+
+        // Eventos do tipo `T` emitidos desde o último `start`
+        pub fn events<T: Discriminator + AnchorDeserialize>() -> Vec<T> {
+            EVENTS.with(|events| {
+                events
+                    .borrow()
+                    .iter()
+                    .filter(|data| data.starts_with(T::DISCRIMINATOR.as_ref()))
+                    .map(|data| T::try_from_slice(&data[8..]).unwrap())
+                    .collect()
+            })
+        }
+
+        // Linhas de `msg!` desde o último `start`
+        pub fn logs() -> Vec<String> {
+            LOGS.with(|logs| logs.borrow().clone())
+        }
+
+        // Conta de teste, guardada no Bank pela chave
+        #[derive(Clone)]
+        pub struct TestAccount {
+            pub lamports: u64,
+            pub data: Vec<u8>,
+            pub owner: Pubkey,
+            pub executable: bool,
+        }
+
+        impl TestAccount {
+            pub fn new(owner: Pubkey, data: Vec<u8>) -> Self {
+                TestAccount { lamports: 1_000_000_000, data, owner, executable: false }
+            }
+
+            pub fn wallet() -> Self {
+                Self::new(system_program::ID, Vec::new())
+            }
+
+            pub fn program() -> Self {
+                TestAccount {
+                    executable: true,
+                    ..Self::new(anchor_lang::solana_program::bpf_loader::ID, Vec::new())
+                }
+            }
+
+            // Conta do programa com o tamanho alocado por `init` (8 + INIT_SPACE)
+            pub fn anchor<T: AccountSerialize + Space>(account: &T) -> Self {
+                let mut data = Vec::new();
+                account.try_serialize(&mut data).unwrap();
+                data.resize(8 + T::INIT_SPACE, 0);
+                Self::new(crate::ID, data)
+            }
+
+            // Conta de token (mint ou ATA) empacotada para o token program `owner`
+            pub fn packed<T: Pack>(owner: Pubkey, state: T) -> Self {
+                let mut data = vec![0; T::LEN];
+                T::pack(state, &mut data).unwrap();
+                Self::new(owner, data)
+            }
+        }
+
+        // Contas da "cadeia" de teste. O programa, o System Program e os
+        // programas de token já estão carregados
+        pub struct Bank {
+            accounts: HashMap<Pubkey, TestAccount>,
+        }
+
+        impl Bank {
+            pub fn new() -> Self {
+                let mut bank = Bank { accounts: HashMap::new() };
+                for program in [
+                    crate::ID,
+                    system_program::ID,
+                    spl_token::ID,
+                    spl_token_2022::ID,
+                    anchor_spl::associated_token::ID,
+                ] {
+                    bank.set(program, TestAccount::program());
+                }
+                bank
+            }
+
+            // Carregar um programa externo que não roda aqui (ex. Token
+            // Metadata), emulado por `stub` até o próximo `start`
+            pub fn load(&mut self, program_id: Pubkey, stub: Stub) {
+                self.set(program_id, TestAccount::program());
+                STUBS.with(|stubs| stubs.borrow_mut().push((program_id, stub)));
+            }
+
+            pub fn set(&mut self, key: Pubkey, account: TestAccount) {
+                self.accounts.insert(key, account);
+            }
+
+            pub fn contains(&self, key: &Pubkey) -> bool {
+                self.accounts.contains_key(key)
+            }
+
+            pub fn get(&self, key: &Pubkey) -> &TestAccount {
+                &self.accounts[key]
+            }
+
+            pub fn read<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+                T::try_deserialize(&mut &self.get(key).data[..]).unwrap()
+            }
+
+            // Executar uma instrução do programa pelo entrypoint (validação
+            // das contas, handler e exit que grava as contas de volta)
+            pub fn execute(&mut self, accounts: &impl ToAccountMetas, ix: impl InstructionData) -> ProgramResult {
+                self.process(Instruction::new_with_bytes(crate::ID, &ix.data(), accounts.to_account_metas(None)))
+            }
+
+            // Executar uma instrução de topo, do programa ou de outro programa
+            // carregado (ex. approve/transfer do token program assinados pelo
+            // usuário). Contas ausentes do Bank são criadas como carteiras novas
+            // se assinam, e vazias e sem lamports (a criar pelo programa) caso
+            // contrário. As contas vivem em buffers vazados (válidos durante
+            // `on_cpi`) e só voltam ao Bank se a instrução tiver sucesso, como
+            // no runtime
+            pub fn process(&mut self, instruction: Instruction) -> ProgramResult {
+                let metas = &instruction.accounts;
+                let mut infos: HashMap<Pubkey, AccountInfo<'static>> = HashMap::new();
+                for meta in metas {
+                    let account = self
+                        .accounts
+                        .entry(meta.pubkey)
+                        .or_insert_with(|| {
+                            let lamports = if meta.is_signer { TestAccount::wallet().lamports } else { 0 };
+                            TestAccount { lamports, ..TestAccount::wallet() }
+                        })
+                        .clone();
+                    let info = infos.entry(meta.pubkey).or_insert_with(|| {
+                        AccountInfo::new(
+                            serialized_key(meta.pubkey, account.data.len()),
+                            false,
+                            false,
+                            Box::leak(Box::new(account.lamports)),
+                            serialized_data(&account.data),
+                            Box::leak(Box::new(account.owner)),
+                            account.executable,
+                            0,
+                        )
+                    });
+                    info.is_signer |= meta.is_signer;
+                    info.is_writable |= meta.is_writable;
+                }
+                let ordered: Caller =
+                    metas.iter().map(|meta| infos[&meta.pubkey].clone()).collect::<Vec<_>>().leak();
+
+                CALLER.set(Some(ordered));
+                let result = if instruction.program_id == crate::ID {
+                    crate::entry(&crate::ID, ordered, &instruction.data)
+                } else {
+                    dispatch(&instruction.program_id, ordered, &instruction.data)
+                };
+                CALLER.set(None);
+                CPI_HOOK.take();
+
+                if result.is_ok() {
+                    for (key, info) in infos {
+                        let account = self.accounts.get_mut(&key).unwrap();
+                        account.lamports = info.lamports();
+                        account.data = info.data.borrow().to_vec();
+                        account.owner = *info.owner;
+                    }
+                }
+                result
+            }
+        }
+    }
+
+    #[cfg(all(test, feature = "profiling"))]
+    #[test]
+    fn compute_profile_event_reports_consumed_units() {
+        runtime::start(0);
+        let cu_start = anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
+        emit_compute_profile("claim", cu_start);
+
+        let events = runtime::events::<ComputeProfileEvent>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].op, "claim");
+        assert!(events[0].consumed > 0);
+    }
+
+    fn config_key() -> Pubkey {
+        Pubkey::find_program_address(&[b"config"], &crate::ID).0
+    }
+
+    // Config autêntica no PDA [b"config"], administrada por `admin`
+    fn config_account(admin: Pubkey, config: ConfigAccount) -> runtime::TestAccount {
+        let config_bump = Pubkey::find_program_address(&[b"config"], &crate::ID).1;
+        runtime::TestAccount::anchor(&ConfigAccount { admin, config_bump, ..config })
+    }
+
+    #[test]
+    fn timed_emergency_pause_lifts_after_the_window() {
+        let admin = Pubkey::new_unique();
+        let now = 1_000_000;
+        runtime::start(now);
+        let mut bank = runtime::Bank::new();
+        bank.set(config_key(), config_account(admin, ConfigAccount::default()));
+        let accounts = crate::accounts::EmergencyPause { admin, config: config_key() };
+
+        let pause = crate::instruction::EmergencyPause { reason: "incident".to_string(), duration_secs: 60 };
+        bank.execute(&accounts, pause).unwrap();
+        let config: ConfigAccount = bank.read(&config_key());
+        assert_eq!(config.pause_expires_at, now + 60);
+        assert!(config.is_paused(now + 60));
+        assert!(!config.is_paused(now + 61));
+
+        // Duração 0 = pausa indefinida
+        runtime::set_now(now + 100);
+        let pause = crate::instruction::EmergencyPause { reason: "incident".to_string(), duration_secs: 0 };
+        bank.execute(&accounts, pause).unwrap();
+        let config: ConfigAccount = bank.read(&config_key());
+        assert_eq!(config.pause_expires_at, 0);
+        assert!(config.is_paused(i64::MAX));
+        assert_eq!(runtime::events::<SecurityEvent>().len(), 2);
+    }
+
+    fn program_error(code: ErrorCode) -> ProgramError {
+        error!(code).into()
+    }
+
+    // Config com limites folgados para os testes de claim pelo entrypoint
+    fn claim_config() -> ConfigAccount {
+        ConfigAccount {
+            max_claim_amount: 1_000_000,
+            max_claim_per_user: 1_000_000,
+            total_supply_limit: 1_000_000_000,
+            max_admin_mint_per_call: u64::MAX,
+            emission: EmissionConfig { initial_rate: BPS_DENOMINATOR, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    // Claim de ponta a ponta pelo entrypoint: mint Token-2022 com a authority
+    // no PDA do programa, conta de claim e ATA do claimer já criadas (para
+    // ler o nonce antes do primeiro claim) e o ed25519 do backend (SIGNER)
+    // antes do claim
+    struct ClaimFixture {
+        bank: runtime::Bank,
+        admin: Pubkey,
+        claimer: Pubkey,
+        mint: Pubkey,
+        token_program: Pubkey,
+        now: i64,
+    }
+
+    impl ClaimFixture {
+        fn new(config: ConfigAccount) -> Self {
+            Self::with_token_program(config, spl_token_2022::ID)
+        }
+
+        fn with_token_program(config: ConfigAccount, token_program: Pubkey) -> Self {
+            use anchor_spl::token_interface::spl_token_2022::state::Mint;
+
+            let now = 10 * ONE_WEEK_SECONDS;
+            runtime::start(now);
+            let (mint_authority, mint_authority_bump) =
+                Pubkey::find_program_address(&[b"mint_authority"], &crate::ID);
+            let mut fixture = ClaimFixture {
+                bank: runtime::Bank::new(),
+                admin: Pubkey::new_unique(),
+                claimer: Pubkey::new_unique(),
+                mint: Pubkey::new_unique(),
+                token_program,
+                now,
+            };
+            let config = ConfigAccount {
+                payment_token_mint: fixture.mint,
+                mint_authority,
+                mint_authority_bump,
+                backend_authority: SIGNER,
+                ..config
+            };
+            fixture.bank.set(config_key(), config_account(fixture.admin, config));
+            let mint = Mint {
+                mint_authority: COption::Some(mint_authority),
+                decimals: 9,
+                is_initialized: true,
+                ..Mint::default()
+            };
+            fixture.bank.set(fixture.mint, runtime::TestAccount::packed(token_program, mint));
+            let claimer = fixture.claimer;
+            fixture.create_ata(&claimer);
+            // Como criada por add_to_blacklist/suspend_user: dono e bump, sem claim
+            let (user_claim, bump) = Pubkey::find_program_address(&[b"user_claim", claimer.as_ref()], &crate::ID);
+            let account = UserClaimAccount { user: claimer, bump, ..Default::default() };
+            fixture.bank.set(user_claim, runtime::TestAccount::anchor(&account));
+            fixture
+        }
+
+        fn user_claim_key(&self) -> Pubkey {
+            Pubkey::find_program_address(&[b"user_claim", self.claimer.as_ref()], &crate::ID).0
+        }
+
+        fn ata(&self, owner: &Pubkey) -> Pubkey {
+            get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+        }
+
+        fn create_ata(&mut self, owner: &Pubkey) -> Pubkey {
+            use anchor_spl::token_interface::spl_token_2022::state::{Account, AccountState};
+
+            let ata = self.ata(owner);
+            let account = Account {
+                mint: self.mint,
+                owner: *owner,
+                state: AccountState::Initialized,
+                ..Account::default()
+            };
+            self.bank.set(ata, runtime::TestAccount::packed(self.token_program, account));
+            ata
+        }
+
+        fn user_claim(&self) -> UserClaimAccount {
+            self.bank.read(&self.user_claim_key())
+        }
+
+        fn set_now(&mut self, now: i64) {
+            self.now = now;
+            runtime::set_now(now);
+        }
+
+        // Lida com extensões (ATAs do Token-2022 criadas com ImmutableOwner)
+        fn balance(&self, owner: &Pubkey) -> u64 {
+            use anchor_spl::token_interface::spl_token_2022::{extension::StateWithExtensions, state::Account};
+
+            let data = &self.bank.get(&self.ata(owner)).data;
+            StateWithExtensions::<Account>::unpack(data).unwrap().base.amount
+        }
+
+        fn args(&self, amount: u64) -> ClaimArgs {
+            ClaimArgs {
+                amount,
+                timestamp: self.now,
+                signature: [7u8; 64],
+                mode: ClaimMode::AllOrNothing,
+                recipient: Pubkey::default(),
+                expiry_ts: 0,
+                category: DEFAULT_CLAIM_CATEGORY,
+                game_session_hash: NO_GAME_SESSION,
+            }
+        }
+
+        // Contas de claim_tokens para o token principal
+        fn claim_accounts(&self) -> crate::accounts::ClaimTokens {
+            let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID).0;
+            let claimer = self.claimer.as_ref();
+            crate::accounts::ClaimTokens {
+                claimer: self.claimer,
+                token_mint: self.mint,
+                claimer_token_account: self.ata(&self.claimer),
+                recipient_token_account: None,
+                treasury_token_account: None,
+                registered_token: None,
+                user_claim_account: self.user_claim_key(),
+                user_status: self.user_claim_key(),
+                claim_role: None,
+                allowlist_entry: None,
+                user_registration: None,
+                escrow: None,
+                claimed_session: None,
+                stats: None,
+                blacklist: pda(&[b"blacklist"]),
+                blacklist_entry: pda(&[b"blacklist_entry", claimer]),
+                backend_authority: SIGNER,
+                mint_authority: pda(&[b"mint_authority"]),
+                config: config_key(),
+                sysvar_instructions: sysvar_instructions::ID,
+                token_program: self.token_program,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::ID,
+            }
+        }
+
+        // Claim assinado pelo backend com o nonce atual do usuário
+        fn claim(&mut self, args: ClaimArgs) -> ProgramResult {
+            let nonce = self.user_claim().claim_nonce;
+            self.claim_signed(args, nonce)
+        }
+
+        fn claim_signed(&mut self, args: ClaimArgs, nonce: u64) -> ProgramResult {
+            let accounts = self.claim_accounts();
+            self.claim_with(&accounts, args, nonce)
+        }
+
+        fn claim_with(
+            &mut self,
+            accounts: &crate::accounts::ClaimTokens,
+            args: ClaimArgs,
+            nonce: u64,
+        ) -> ProgramResult {
+            self.sign(&claim_message(&self.claimer, &self.mint, &args, nonce), &args.signature);
+            self.bank.execute(accounts, crate::instruction::ClaimTokens { args })
+        }
+
+        // Sysvar de instruções com o ed25519 do backend antes da instrução do programa
+        fn sign(&mut self, message: &str, signature: &[u8; 64]) {
+            let ed25519 = (ed25519_program::ID, ed25519_ix_data(message.as_bytes(), signature, &SIGNER));
+            let sysvar = instructions_sysvar_data(&[ed25519, (crate::ID, Vec::new())], 1);
+            self.bank.set(
+                sysvar_instructions::ID,
+                runtime::TestAccount::new(anchor_lang::solana_program::sysvar::ID, sysvar),
+            );
+        }
+
+        fn user_burn_key(&self) -> Pubkey {
+            Pubkey::find_program_address(&[b"user_burn", self.claimer.as_ref()], &crate::ID).0
+        }
+
+        // Contas de burn_tokens do claimer, com a conta de queimas já criada
+        fn burn_accounts(&mut self) -> crate::accounts::BurnTokens {
+            let user_burn = self.user_burn_key();
+            if !self.bank.contains(&user_burn) {
+                let account = UserBurnAccount { user: self.claimer, burn_nonce: 0 };
+                self.bank.set(user_burn, runtime::TestAccount::anchor(&account));
+            }
+            crate::accounts::BurnTokens {
+                payer: self.claimer,
+                payment_token_mint: self.mint,
+                payer_payment_token_account: self.ata(&self.claimer),
+                user_burn_account: user_burn,
+                registered_token: None,
+                backend_authority: SIGNER,
+                stats: None,
+                config: config_key(),
+                sysvar_instructions: sysvar_instructions::ID,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            }
+        }
+
+        fn burn_ix(&self, amount: u64) -> crate::instruction::BurnTokens {
+            crate::instruction::BurnTokens {
+                amount,
+                timestamp: self.now,
+                signature: [7u8; 64],
+                description: "test burn".to_string(),
+                burn_reason_code: BURN_REASON_UNSPECIFIED,
+                reference_id: 0,
+            }
+        }
+
+        // Contas de mint_tokens do admin para a ATA de `recipient`
+        fn mint_accounts(&self, recipient: Pubkey) -> crate::accounts::MintTokens {
+            crate::accounts::MintTokens {
+                admin: self.admin,
+                rent_payer: self.admin,
+                token_mint: self.mint,
+                recipient_token_account: self.ata(&recipient),
+                recipient,
+                registered_token: None,
+                mint_authority: Pubkey::find_program_address(&[b"mint_authority"], &crate::ID).0,
+                stats: None,
+                config: config_key(),
+                token_program: self.token_program,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::ID,
+            }
+        }
+
+        fn pending_action_key(&self) -> Pubkey {
+            Pubkey::find_program_address(&[b"pending_action", self.admin.as_ref()], &crate::ID).0
+        }
+
+        // Ação administrativa solicitada agora pelo admin (executável após o delay)
+        fn request_action(&mut self, action_type: AdminActionType, new_value: Pubkey, new_amount: u64) -> ProgramResult {
+            let accounts = crate::accounts::RequestAdminAction {
+                admin: self.admin,
+                pending_action: self.pending_action_key(),
+                config: config_key(),
+                system_program: system_program::ID,
+            };
+            let ix = crate::instruction::RequestAdminAction { action_type, new_value, new_amount };
+            self.bank.execute(&accounts, ix)
+        }
+
+        fn execute_action(&mut self) -> ProgramResult {
+            let accounts = crate::accounts::ExecuteAdminAction {
+                admin: self.admin,
+                pending_action: self.pending_action_key(),
+                config: config_key(),
+            };
+            self.bank.execute(&accounts, crate::instruction::ExecuteAdminAction {})
+        }
+
+        // Queima assinada pelo backend com o nonce de queimas atual do claimer
+        fn burn(&mut self, ix: crate::instruction::BurnTokens) -> ProgramResult {
+            let accounts = self.burn_accounts();
+            let nonce = self.bank.read::<UserBurnAccount>(&self.user_burn_key()).burn_nonce;
+            let message = burn_message(
+                &self.claimer,
+                &self.mint,
+                ix.amount,
+                ix.timestamp,
+                nonce,
+                ix.burn_reason_code,
+                ix.reference_id,
+            );
+            self.sign(&message, &ix.signature);
+            self.bank.execute(&accounts, ix)
+        }
+    }
+
+    #[test]
+    fn reset_user_nonce_lets_a_desynced_user_claim_again() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(100)).unwrap();
+        assert_eq!(fixture.user_claim().claim_nonce, 1);
+
+        // O backend assinou com o nonce 5, mas a conta ficou em 1
+        assert_eq!(
+            fixture.claim_signed(fixture.args(100), 5).unwrap_err(),
+            program_error(ErrorCode::InvalidSignature)
+        );
+
+        let reset = crate::accounts::ResetUserNonce {
+            admin: fixture.admin,
+            user: fixture.claimer,
+            registered_token: None,
+            user_claim_account: fixture.user_claim_key(),
+            pending_action: None,
+            config: config_key(),
+        };
+        // O nonce só avança
+        assert_eq!(
+            fixture.bank.execute(&reset, crate::instruction::ResetUserNonce { new_nonce: 1 }).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
+        fixture.bank.execute(&reset, crate::instruction::ResetUserNonce { new_nonce: 5 }).unwrap();
+        assert_eq!(runtime::events::<SecurityEvent>().last().unwrap().event_type, "USER_NONCE_RESET");
+
+        fixture.claim_signed(fixture.args(100), 5).unwrap();
+        assert_eq!(fixture.user_claim().claim_nonce, 6);
+        assert_eq!(fixture.balance(&fixture.claimer), 200);
+    }
+
+    #[test]
+    fn fee_exempt_claim_roles_skip_the_treasury_fee() {
+        let (treasury, treasury_bump) = Pubkey::find_program_address(&[b"treasury"], &crate::ID);
+        let mut fixture = ClaimFixture::new(ConfigAccount { claim_fee_bps: 100, treasury_bump, ..claim_config() });
+        let treasury_ata = fixture.create_ata(&treasury);
+        let accounts = crate::accounts::ClaimTokens {
+            treasury_token_account: Some(treasury_ata),
+            ..fixture.claim_accounts()
+        };
+
+        // Sem papel: 1% de taxa mintada ao tesouro além do claim
+        fixture.claim_with(&accounts, fixture.args(10_000), 0).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 10_000);
+        assert_eq!(fixture.balance(&treasury), 100);
+        assert_eq!(runtime::events::<TokenClaimEvent>()[0].fee_amount, 100);
+
+        // Papel isento: nenhuma taxa, e o evento registra fee_amount = 0
+        let (claim_role, _) =
+            Pubkey::find_program_address(&[b"claim_role", fixture.claimer.as_ref()], &crate::ID);
+        let role = ClaimRole {
+            user: fixture.claimer,
+            limit_multiplier: BPS_DENOMINATOR as u16,
+            unlimited: false,
+            fee_exempt: true,
+        };
+        fixture.bank.set(claim_role, runtime::TestAccount::anchor(&role));
+        let accounts = crate::accounts::ClaimTokens { claim_role: Some(claim_role), ..accounts };
+        fixture.claim_with(&accounts, fixture.args(10_000), 1).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 20_000);
+        assert_eq!(fixture.balance(&treasury), 100);
+        assert_eq!(runtime::events::<TokenClaimEvent>()[1].fee_amount, 0);
+    }
+
+    #[test]
+    fn spoofed_config_cannot_pass_the_admin_check() {
+        let admin = Pubkey::new_unique();
+        let attacker = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        runtime::start(1_000);
+        let mut bank = runtime::Bank::new();
+        bank.set(config_key(), config_account(admin, ConfigAccount::default()));
+        let (blacklist, bump) = Pubkey::find_program_address(&[b"blacklist"], &crate::ID);
+        bank.set(
+            blacklist,
+            runtime::TestAccount::anchor(&BlacklistAccount { admin, blacklisted_users: vec![user], bump }),
+        );
+        let (user_claim, bump) = Pubkey::find_program_address(&[b"user_claim", user.as_ref()], &crate::ID);
+        bank.set(
+            user_claim,
+            runtime::TestAccount::anchor(&UserClaimAccount { user, is_blacklisted: true, bump, ..Default::default() }),
+        );
+        // Cópia da config fora do PDA, com o atacante como admin
+        let spoofed = Pubkey::new_unique();
+        bank.set(spoofed, config_account(attacker, ConfigAccount::default()));
+
+        let accounts = |admin, config| crate::accounts::ManageBlacklist {
+            admin,
+            blacklist,
+            user_claim_account: user_claim,
+            blacklist_entry: None,
+            user,
+            config,
+        };
+        let remove = || crate::instruction::RemoveFromBlacklist { user };
+        assert_eq!(
+            bank.execute(&accounts(attacker, spoofed), remove()).unwrap_err(),
+            ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintSeeds as u32)
+        );
+        assert_eq!(
+            bank.execute(&accounts(attacker, config_key()), remove()).unwrap_err(),
+            program_error(ErrorCode::Unauthorized)
+        );
+        assert!(bank.read::<BlacklistAccount>(&blacklist).blacklisted_users.contains(&user));
+
+        bank.execute(&accounts(admin, config_key()), remove()).unwrap();
+        assert!(bank.read::<BlacklistAccount>(&blacklist).blacklisted_users.is_empty());
+        assert!(!bank.read::<UserClaimAccount>(&user_claim).is_blacklisted);
+    }
+
+    #[test]
+    fn suspended_users_can_claim_again_after_expiry() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let suspend = crate::accounts::SuspendUser {
+            admin: fixture.admin,
+            user: fixture.claimer,
+            user_claim_account: fixture.user_claim_key(),
+            config: config_key(),
+            system_program: system_program::ID,
+        };
+        fixture.bank.execute(&suspend, crate::instruction::SuspendUser { duration: 3_600 }).unwrap();
+        let suspended_until = fixture.now + 3_600;
+        assert_eq!(fixture.user_claim().suspended_until, suspended_until);
+
+        assert_eq!(
+            fixture.claim(fixture.args(100)).unwrap_err(),
+            program_error(ErrorCode::UserSuspended)
+        );
+        fixture.set_now(suspended_until - 1);
+        assert_eq!(
+            fixture.claim(fixture.args(100)).unwrap_err(),
+            program_error(ErrorCode::UserSuspended)
+        );
+
+        // No prazo a suspensão deixa de valer e o claim a limpa
+        fixture.set_now(suspended_until);
+        fixture.claim(fixture.args(100)).unwrap();
+        assert_eq!(fixture.user_claim().suspended_until, 0);
+        assert_eq!(fixture.balance(&fixture.claimer), 100);
+    }
+
+    // Um hook do token que reentra com o mesmo payload assinado encontra o
+    // nonce já gravado (exit antes do CPI) e falha; só o claim externo minta
+    #[test]
+    fn reentrant_claim_cannot_replay_the_signature() {
+        use anchor_lang::InstructionData;
+        use std::{cell::Cell, rc::Rc};
+
+        let mut fixture = ClaimFixture::new(claim_config());
+        let reentry = Rc::new(Cell::new(None));
+        let result = reentry.clone();
+        let data = crate::instruction::ClaimTokens { args: fixture.args(100) }.data();
+        runtime::on_cpi(move |accounts| result.set(Some(crate::entry(&crate::ID, accounts, &data))));
+
+        fixture.claim(fixture.args(100)).unwrap();
+        assert_eq!(reentry.take().unwrap().unwrap_err(), program_error(ErrorCode::InvalidSignature));
+        assert_eq!(fixture.user_claim().claim_nonce, 1);
+        assert_eq!(fixture.balance(&fixture.claimer), 100);
+    }
+
+    #[test]
+    fn reentrant_burn_cannot_replay_the_signature() {
+        use anchor_lang::InstructionData;
+        use std::{cell::Cell, rc::Rc};
+
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(100)).unwrap();
+        let reentry = Rc::new(Cell::new(None));
+        let result = reentry.clone();
+        let data = fixture.burn_ix(40).data();
+        runtime::on_cpi(move |accounts| result.set(Some(crate::entry(&crate::ID, accounts, &data))));
+
+        fixture.burn(fixture.burn_ix(40)).unwrap();
+        assert_eq!(reentry.take().unwrap().unwrap_err(), program_error(ErrorCode::InvalidSignature));
+        assert_eq!(fixture.bank.read::<UserBurnAccount>(&fixture.user_burn_key()).burn_nonce, 1);
+        assert_eq!(fixture.balance(&fixture.claimer), 60);
+    }
+
+    #[test]
+    fn claims_above_the_instant_threshold_split_into_the_escrow() {
+        let mut fixture = ClaimFixture::new(ConfigAccount {
+            instant_claim_threshold: 1_000,
+            escrow_release_delay: 3_600,
+            ..claim_config()
+        });
+
+        // Abaixo do limite: tudo na hora, sem escrow
+        fixture.claim(fixture.args(600)).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 600);
+        assert!(runtime::events::<EscrowEvent>().is_empty());
+
+        // Acima do limite o escrow é obrigatório
+        assert_eq!(
+            fixture.claim(fixture.args(2_500)).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
+
+        // 1.000 na hora e 1.500 no escrow (criado pelo init_if_needed), que já
+        // contam no total mintado
+        let (escrow, _) = Pubkey::find_program_address(&[b"escrow", fixture.claimer.as_ref()], &crate::ID);
+        let accounts = crate::accounts::ClaimTokens { escrow: Some(escrow), ..fixture.claim_accounts() };
+        fixture.claim_with(&accounts, fixture.args(2_500), 1).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 1_600);
+        let unlock_at = fixture.now + 3_600;
+        let account: EscrowAccount = fixture.bank.read(&escrow);
+        assert_eq!((account.user, account.amount, account.unlock_at), (fixture.claimer, 1_500, unlock_at));
+        assert_eq!(fixture.bank.read::<ConfigAccount>(&config_key()).total_minted, 3_100);
+        assert_eq!(fixture.user_claim().last_claim_amount, 1_000);
+        let events = runtime::events::<EscrowEvent>();
+        assert_eq!((events[0].action.as_str(), events[0].amount), ("DEPOSIT", 1_500));
+
+        let release = crate::accounts::ReleaseEscrow {
+            user: fixture.claimer,
+            escrow,
+            token_mint: fixture.mint,
+            user_token_account: fixture.ata(&fixture.claimer),
+            mint_authority: Pubkey::find_program_address(&[b"mint_authority"], &crate::ID).0,
+            config: config_key(),
+            token_program: fixture.token_program,
+        };
+        fixture.set_now(unlock_at - 1);
+        assert_eq!(
+            fixture.bank.execute(&release, crate::instruction::ReleaseEscrow {}).unwrap_err(),
+            program_error(ErrorCode::EscrowLocked)
+        );
+        fixture.set_now(unlock_at);
+        fixture.bank.execute(&release, crate::instruction::ReleaseEscrow {}).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 3_100);
+        assert_eq!(fixture.bank.read::<EscrowAccount>(&escrow).amount, 0);
+        assert_eq!(runtime::events::<EscrowEvent>()[1].action, "RELEASE");
+    }
+
+    #[test]
+    fn initialize_config_sets_all_authorities_in_one_call() {
+        use anchor_spl::token_interface::spl_token_2022::state::Mint;
+
+        runtime::start(1_000);
+        let mut bank = runtime::Bank::new();
+        let admin = Pubkey::new_unique();
+        let token_mint = Pubkey::new_unique();
+        let (mint_authority, mint_authority_bump) =
+            Pubkey::find_program_address(&[b"mint_authority"], &crate::ID);
+        let mint = Mint {
+            mint_authority: COption::Some(mint_authority),
+            decimals: 6,
+            is_initialized: true,
+            ..Mint::default()
+        };
+        bank.set(token_mint, runtime::TestAccount::packed(spl_token_2022::ID, mint));
+        let accounts = crate::accounts::InitializeConfig {
+            admin,
+            token_mint,
+            config: config_key(),
+            mint_authority,
+            system_program: system_program::ID,
+        };
+        let (backend, guardian) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let initialize = |backend_authority, guardian, mint_authority| crate::instruction::InitializeConfig {
+            payment_token_mint: token_mint,
+            max_claim_per_user: 1_000_000,
+            total_supply_limit: 1_000_000_000,
+            backend_authority,
+            guardian,
+            mint_authority,
+        };
+
+        // Nenhuma autoridade pode ficar em Pubkey::default()
+        for ix in [
+            initialize(Pubkey::default(), guardian, mint_authority),
+            initialize(backend, Pubkey::default(), mint_authority),
+            initialize(backend, guardian, Pubkey::default()),
+        ] {
+            assert_eq!(bank.execute(&accounts, ix).unwrap_err(), program_error(ErrorCode::InvalidInput));
+        }
+        assert_eq!(bank.get(&config_key()).owner, system_program::ID);
+
+        bank.execute(&accounts, initialize(backend, guardian, mint_authority)).unwrap();
+        let config: ConfigAccount = bank.read(&config_key());
+        assert_eq!(config.admin, admin);
+        assert_eq!(config.backend_authority, backend);
+        assert_eq!(config.guardians, vec![guardian]);
+        assert_eq!(config.guardian_threshold, 0);
+        assert_eq!((config.mint_authority, config.mint_authority_bump), (mint_authority, mint_authority_bump));
+        assert_eq!(config.decimals, 6);
+    }
+
+    // Só a janela semanal é fixa; diário e horário deslizam sobre o histórico
+    #[test]
+    fn weekly_rollover_emits_a_limit_reset_event() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let start = fixture.now;
+        fixture.claim(fixture.args(100)).unwrap();
+        let events = runtime::events::<LimitResetEvent>();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].user, events[0].window_type.as_str()), (fixture.claimer, "WEEKLY"));
+        assert_eq!(events[0].reset_timestamp, start);
+
+        // Passar da hora e do dia não emite reset
+        fixture.set_now(start + 3_601);
+        fixture.claim(fixture.args(100)).unwrap();
+        fixture.set_now(start + 86_401);
+        fixture.claim(fixture.args(100)).unwrap();
+        assert_eq!(runtime::events::<LimitResetEvent>().len(), 1);
+        assert_eq!(fixture.user_claim().weekly_claimed, 300);
+
+        fixture.set_now(start + ONE_WEEK_SECONDS);
+        fixture.claim(fixture.args(100)).unwrap();
+        let events = runtime::events::<LimitResetEvent>();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].reset_timestamp, start + ONE_WEEK_SECONDS);
+        assert_eq!(fixture.user_claim().weekly_claimed, 100);
+    }
+
+    #[test]
+    fn wrong_mint_authority_fails_fast() {
+        use anchor_spl::token_interface::spl_token_2022::state::Mint;
+
+        let mut fixture = ClaimFixture::new(claim_config());
+        let verify = crate::accounts::VerifyMintAuthority {
+            token_mint: fixture.mint,
+            registered_token: None,
+            config: config_key(),
+        };
+        fixture.bank.execute(&verify, crate::instruction::VerifyMintAuthority {}).unwrap();
+
+        // Deploy em que a authority do mint não foi transferida ao PDA
+        let mint = Mint {
+            mint_authority: COption::Some(Pubkey::new_unique()),
+            decimals: 9,
+            is_initialized: true,
+            ..Mint::default()
+        };
+        fixture.bank.set(fixture.mint, runtime::TestAccount::packed(fixture.token_program, mint));
+        assert_eq!(
+            fixture.bank.execute(&verify, crate::instruction::VerifyMintAuthority {}).unwrap_err(),
+            program_error(ErrorCode::MintAuthorityMismatch)
+        );
+        assert_eq!(
+            fixture.claim(fixture.args(100)).unwrap_err(),
+            program_error(ErrorCode::MintAuthorityMismatch)
+        );
+    }
+
+    // Assinaturas rejeitadas revertem o claim, mas o SIG_REJECT com o motivo
+    // fica nos logs da transação
+    #[test]
+    fn rejected_signatures_leave_a_sig_reject_trail() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let accounts = fixture.claim_accounts();
+
+        // Backend assinou 100; o atacante envia 900 com a mesma assinatura
+        let signed = fixture.args(100);
+        fixture.sign(&claim_message(&fixture.claimer, &fixture.mint, &signed, 0), &signed.signature);
+        let forged = ClaimArgs { amount: 900, ..signed };
+        assert_eq!(
+            fixture.bank.execute(&accounts, crate::instruction::ClaimTokens { args: forged }).unwrap_err(),
+            program_error(ErrorCode::InvalidSignature)
+        );
+
+        // Sem instrução ed25519
+        let sysvar = instructions_sysvar_data(&[(crate::ID, Vec::new())], 0);
+        fixture.bank.set(
+            sysvar_instructions::ID,
+            runtime::TestAccount::new(anchor_lang::solana_program::sysvar::ID, sysvar),
+        );
+        assert_eq!(
+            fixture.bank.execute(&accounts, crate::instruction::ClaimTokens { args: fixture.args(100) }).unwrap_err(),
+            program_error(ErrorCode::MissingEd25519Instruction)
+        );
+
+        // Assinatura válida, mas com timestamp no futuro
+        let future = ClaimArgs { timestamp: fixture.now + 60, ..fixture.args(100) };
+        assert_eq!(
+            fixture.claim(future).unwrap_err(),
+            program_error(ErrorCode::SignatureFromFuture)
+        );
+
+        let rejections: Vec<_> = runtime::events::<SecurityEvent>()
+            .into_iter()
+            .map(|event| {
+                assert_eq!((event.event_type.as_str(), event.user), ("SIG_REJECT", fixture.claimer));
+                event.reason
+            })
+            .collect();
+        assert_eq!(rejections, ["InvalidSignature", "MissingEd25519Instruction", "SignatureFromFuture"]);
+        assert_eq!(fixture.user_claim().claim_nonce, 0);
+        assert_eq!(fixture.balance(&fixture.claimer), 0);
+    }
+
+    // Claim, queima e mint do admin pelo mesmo código com mints do SPL Token
+    // clássico e do Token-2022
+    #[test]
+    fn claims_burns_and_mints_work_on_both_token_programs() {
+        use anchor_spl::token::spl_token;
+
+        for token_program in [spl_token_2022::ID, spl_token::ID] {
+            let mut fixture = ClaimFixture::with_token_program(claim_config(), token_program);
+            fixture.claim(fixture.args(100)).unwrap();
+            fixture.burn(fixture.burn_ix(40)).unwrap();
+            let mint = crate::instruction::MintTokens { amount: 500, recipient: fixture.claimer };
+            fixture.bank.execute(&fixture.mint_accounts(fixture.claimer), mint).unwrap();
+            assert_eq!(fixture.balance(&fixture.claimer), 560);
+            assert_eq!(fixture.bank.get(&fixture.mint).owner, token_program);
+
+            // O token program precisa ser o dono do mint
+            let other = if token_program == spl_token::ID { spl_token_2022::ID } else { spl_token::ID };
+            let accounts = crate::accounts::MintTokens { token_program: other, ..fixture.mint_accounts(fixture.claimer) };
+            let mint = crate::instruction::MintTokens { amount: 500, recipient: fixture.claimer };
+            assert_eq!(
+                fixture.bank.execute(&accounts, mint).unwrap_err(),
+                ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintAssociatedTokenTokenProgram as u32)
+            );
+            assert_eq!(fixture.balance(&fixture.claimer), 560);
+        }
+    }
+
+    // Airdrop para quem ainda não tem ATA: o init_if_needed cria a conta pelo
+    // programa de ATA, com o rent pago pelo rent_payer e não pelo admin
+    #[test]
+    fn mint_tokens_creates_the_missing_recipient_ata() {
+        use anchor_lang::solana_program::program_pack::Pack;
+
+        let mut fixture = ClaimFixture::new(claim_config());
+        let recipient = Pubkey::new_unique();
+        let rent_payer = Pubkey::new_unique();
+        fixture.bank.set(rent_payer, runtime::TestAccount::wallet());
+        let ata = fixture.ata(&recipient);
+        assert!(!fixture.bank.contains(&ata));
+
+        let accounts = crate::accounts::MintTokens { rent_payer, ..fixture.mint_accounts(recipient) };
+        fixture.bank.execute(&accounts, crate::instruction::MintTokens { amount: 500, recipient }).unwrap();
+        assert_eq!(fixture.balance(&recipient), 500);
+        let created = fixture.bank.get(&ata);
+        assert_eq!(created.owner, spl_token_2022::ID);
+        // ATA do Token-2022 com a extensão ImmutableOwner
+        assert!(created.data.len() > spl_token_2022::state::Account::LEN);
+        let rent = Rent::default().minimum_balance(created.data.len());
+        assert_eq!(created.lamports, rent);
+        assert_eq!(fixture.bank.get(&rent_payer).lamports, runtime::TestAccount::wallet().lamports - rent);
+        assert_eq!(fixture.bank.get(&fixture.admin).lamports, runtime::TestAccount::wallet().lamports);
+
+        // ATA existente: só minta
+        fixture.bank.execute(&accounts, crate::instruction::MintTokens { amount: 250, recipient }).unwrap();
+        assert_eq!(fixture.balance(&recipient), 750);
+        assert_eq!(fixture.bank.get(&rent_payer).lamports, runtime::TestAccount::wallet().lamports - rent);
+    }
+
+    // O cliente pede a próxima assinatura com o new_nonce do evento
+    #[test]
+    fn nonce_event_reports_the_next_nonce() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        for expected in 0..3u64 {
+            fixture.claim(fixture.args(100)).unwrap();
+            let event = runtime::events::<NonceEvent>().pop().unwrap();
+            assert_eq!(event.user, fixture.claimer);
+            assert_eq!((event.old_nonce, event.new_nonce), (expected, expected + 1));
+            assert_eq!(event.new_nonce, fixture.user_claim().claim_nonce);
+        }
+        assert_eq!(runtime::events::<NonceEvent>().len(), 3);
+    }
+
+    // A ATA precisa ser a canônica do recipient passado
+    #[test]
+    fn mint_tokens_rejects_a_token_account_of_another_owner() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let recipient = Pubkey::new_unique();
+        let accounts = crate::accounts::MintTokens {
+            recipient_token_account: fixture.ata(&fixture.claimer),
+            ..fixture.mint_accounts(recipient)
+        };
+        let mint = || crate::instruction::MintTokens { amount: 500, recipient };
+        assert_eq!(
+            fixture.bank.execute(&accounts, mint()).unwrap_err(),
+            ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintTokenOwner as u32)
+        );
+
+        // Conta de token do recipient, mas fora do endereço canônico da ATA
+        let non_canonical = Pubkey::new_unique();
+        let state = spl_token_2022::state::Account {
+            mint: fixture.mint,
+            owner: recipient,
+            state: spl_token_2022::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        fixture.bank.set(non_canonical, runtime::TestAccount::packed(spl_token_2022::ID, state));
+        let accounts = crate::accounts::MintTokens { recipient_token_account: non_canonical, ..accounts };
+        assert_eq!(
+            fixture.bank.execute(&accounts, mint()).unwrap_err(),
+            ProgramError::Custom(anchor_lang::error::ErrorCode::AccountNotAssociatedTokenAccount as u32)
+        );
+        assert_eq!(fixture.balance(&fixture.claimer), 0);
+    }
+
+    #[test]
+    fn approved_delegate_burns_within_its_allowance() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(100)).unwrap();
+        let (owner, ata) = (fixture.claimer, fixture.ata(&fixture.claimer));
+        let delegate = Pubkey::new_unique();
+        fixture.bank.set(delegate, runtime::TestAccount::wallet());
+        let approve = spl_token_2022::instruction::approve(&spl_token_2022::ID, &ata, &delegate, &owner, &[], 50).unwrap();
+        fixture.bank.process(approve).unwrap();
+
+        let (mint, user_burn, token_program) = (fixture.mint, fixture.user_burn_key(), fixture.token_program);
+        let accounts = move |delegate| crate::accounts::BurnFromDelegate {
+            delegate,
+            owner,
+            payment_token_mint: mint,
+            owner_token_account: ata,
+            user_burn_account: user_burn,
+            registered_token: None,
+            backend_authority: SIGNER,
+            stats: None,
+            config: config_key(),
+            sysvar_instructions: sysvar_instructions::ID,
+            token_program,
+            system_program: system_program::ID,
+        };
+        let burn = |fixture: &mut ClaimFixture, delegate, amount, nonce| {
+            let ix = crate::instruction::BurnFromDelegate {
+                amount,
+                timestamp: fixture.now,
+                signature: [7u8; 64],
+                description: "settlement".to_string(),
+            };
+            let message = delegated_burn_message(&owner, &delegate, &mint, amount, fixture.now, nonce);
+            fixture.sign(&message, &ix.signature);
+            fixture.bank.execute(&accounts(delegate), ix)
+        };
+
+        burn(&mut fixture, delegate, 30, 0).unwrap();
+        assert_eq!(fixture.balance(&owner), 70);
+        assert_eq!(fixture.bank.read::<UserBurnAccount>(&user_burn).burn_nonce, 1);
+
+        // Outro signatário não é o delegate aprovado
+        assert_eq!(
+            burn(&mut fixture, Pubkey::new_unique(), 10, 1).unwrap_err(),
+            program_error(ErrorCode::InvalidDelegate)
+        );
+        // Restam 20 da aprovação
+        assert_eq!(
+            burn(&mut fixture, delegate, 21, 1).unwrap_err(),
+            program_error(ErrorCode::InsufficientDelegatedAmount)
+        );
+        burn(&mut fixture, delegate, 20, 1).unwrap();
+        assert_eq!(fixture.balance(&owner), 50);
+    }
+
+    #[test]
+    fn frozen_accounts_cannot_transfer_until_thawed() {
+        use anchor_lang::solana_program::program_pack::Pack;
+        use anchor_spl::token_interface::spl_token_2022::{error::TokenError, instruction, state::Mint};
+
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(100)).unwrap();
+        let (owner, ata) = (fixture.claimer, fixture.ata(&fixture.claimer));
+        let receiver = Pubkey::new_unique();
+        let receiver_ata = fixture.create_ata(&receiver);
+        let accounts = |admin| crate::accounts::FreezeThawTokenAccount {
+            admin,
+            token_mint: fixture.mint,
+            token_account: ata,
+            freeze_authority: Pubkey::find_program_address(&[b"freeze_authority"], &crate::ID).0,
+            config: config_key(),
+            token_program: spl_token_2022::ID,
+        };
+        let admin = accounts(fixture.admin);
+        let transfer = || {
+            instruction::transfer_checked(&spl_token_2022::ID, &ata, &fixture.mint, &receiver_ata, &owner, &[], 10, 9)
+                .unwrap()
+        };
+
+        // Sem o PDA como freeze authority do mint
+        assert_eq!(
+            fixture.bank.execute(&admin, crate::instruction::FreezeAccount {}).unwrap_err(),
+            program_error(ErrorCode::FreezeAuthorityMismatch)
+        );
+        let mut mint = Mint::unpack(&fixture.bank.get(&fixture.mint).data).unwrap();
+        mint.freeze_authority = COption::Some(admin.freeze_authority);
+        fixture.bank.set(fixture.mint, runtime::TestAccount::packed(spl_token_2022::ID, mint));
+
+        assert_eq!(
+            fixture.bank.execute(&accounts(owner), crate::instruction::FreezeAccount {}).unwrap_err(),
+            program_error(ErrorCode::Unauthorized)
+        );
+        fixture.bank.execute(&admin, crate::instruction::FreezeAccount {}).unwrap();
+        assert_eq!(
+            fixture.bank.process(transfer()).unwrap_err(),
+            ProgramError::Custom(TokenError::AccountFrozen as u32)
+        );
+        assert_eq!(runtime::events::<SecurityEvent>().last().unwrap().event_type, "ACCOUNT_FROZEN");
+
+        fixture.bank.execute(&admin, crate::instruction::ThawAccount {}).unwrap();
+        fixture.bank.process(transfer()).unwrap();
+        assert_eq!((fixture.balance(&owner), fixture.balance(&receiver)), (90, 10));
+    }
+
+    #[test]
+    fn claim_burn_and_mint_events_carry_increasing_global_seq() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(100)).unwrap();
+        fixture.burn(fixture.burn_ix(40)).unwrap();
+        let mint = crate::instruction::MintTokens { amount: 500, recipient: fixture.claimer };
+        fixture.bank.execute(&fixture.mint_accounts(fixture.claimer), mint).unwrap();
+
+        let claim_seq = runtime::events::<TokenClaimEvent>()[0].global_seq;
+        let burn_seq = runtime::events::<TokenBurnEvent>()[0].global_seq;
+        let mint_seq = runtime::events::<TokenMintEvent>().last().unwrap().global_seq;
+        assert!(claim_seq < burn_seq && burn_seq < mint_seq);
+        assert_eq!(fixture.bank.read::<ConfigAccount>(&config_key()).global_seq, mint_seq);
+    }
+
+    // Todo evento com global_seq consome um número: o indexador detecta lacunas
+    #[test]
+    fn events_across_claim_burn_and_pause_have_no_seq_gaps() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(100)).unwrap();
+        fixture.burn(fixture.burn_ix(40)).unwrap();
+        let pause = crate::accounts::EmergencyPause { admin: fixture.admin, config: config_key() };
+        let reason = "incident".to_string();
+        fixture.bank.execute(&pause, crate::instruction::EmergencyPause { reason, duration_secs: 0 }).unwrap();
+
+        let mut seqs = Vec::new();
+        macro_rules! collect_seqs {
+            ($($event:ty),*) => {
+                $(seqs.extend(runtime::events::<$event>().iter().map(|event| event.global_seq));)*
+            };
+        }
+        collect_seqs!(
+            TokenClaimEvent, TokenMintEvent, TokenBurnEvent, ClaimLimitsEvent, NonceEvent,
+            LimitResetEvent, SecurityEvent, AdminActionEvent, TreasuryEvent, EscrowEvent
+        );
+        seqs.sort_unstable();
+        let last = fixture.bank.read::<ConfigAccount>(&config_key()).global_seq;
+        assert_eq!(seqs, (1..=last).collect::<Vec<_>>());
+        assert_eq!(runtime::events::<SecurityEvent>().last().unwrap().global_seq, last);
+    }
+
+    #[test]
+    fn claim_limits_event_reports_the_remaining_allowance() {
+        // 2.400 por dia => 100 por hora
+        let mut fixture = ClaimFixture::new(ConfigAccount { max_claim_per_user: 2_400, ..claim_config() });
+        fixture.claim(fixture.args(60)).unwrap();
+        fixture.claim(fixture.args(30)).unwrap();
+        let event = runtime::events::<ClaimLimitsEvent>().pop().unwrap();
+        assert_eq!((event.user, event.category), (fixture.claimer, DEFAULT_CLAIM_CATEGORY));
+        assert_eq!((event.daily_claimed, event.daily_remaining), (90, 2_310));
+        assert_eq!((event.hourly_claimed, event.hourly_remaining), (90, 10));
+
+        // Uma hora depois os 90 saíram da janela horária, mas não da diária
+        fixture.set_now(fixture.now + 3_601);
+        fixture.claim(fixture.args(100)).unwrap();
+        let event = runtime::events::<ClaimLimitsEvent>().pop().unwrap();
+        assert_eq!((event.daily_claimed, event.daily_remaining), (190, 2_210));
+        assert_eq!((event.hourly_claimed, event.hourly_remaining), (100, 0));
+        assert_eq!(
+            fixture.claim(fixture.args(1)).unwrap_err(),
+            program_error(ErrorCode::InvalidPaymentAmount)
+        );
+    }
+
+    #[test]
+    fn claim_roles_scale_or_lift_the_limits() {
+        let config = claim_config();
+        let role = |limit_multiplier, unlimited| ClaimRole {
+            user: Pubkey::default(),
+            limit_multiplier,
+            unlimited,
+            fee_exempt: false,
+        };
+        assert_eq!(claim_limits(&config, 2_400, None, 0).unwrap(), (2_400, 100));
+        assert_eq!(claim_limits(&config, 2_400, Some(&role(20_000, false)), 0).unwrap(), (4_800, 200));
+        assert_eq!(claim_limits(&config, 2_400, Some(&role(0, true)), 0).unwrap(), (u64::MAX, u64::MAX));
+
+        // Pelo entrypoint: sem papel, 100 por hora
+        let mut fixture = ClaimFixture::new(ConfigAccount { max_claim_per_user: 2_400, ..claim_config() });
+        assert_eq!(
+            fixture.claim(fixture.args(101)).unwrap_err(),
+            program_error(ErrorCode::InvalidPaymentAmount)
+        );
+        let (claim_role, _) = Pubkey::find_program_address(&[b"claim_role", fixture.claimer.as_ref()], &crate::ID);
+        let set_role = crate::accounts::SetClaimRole {
+            admin: fixture.admin,
+            user: fixture.claimer,
+            claim_role,
+            config: config_key(),
+            system_program: system_program::ID,
+        };
+        let role_ix = |limit_multiplier, unlimited| crate::instruction::SetClaimRole {
+            limit_multiplier,
+            unlimited,
+            fee_exempt: false,
+        };
+        // Só o admin define papéis
+        let by_user = crate::accounts::SetClaimRole { admin: fixture.claimer, ..set_role };
+        assert_eq!(
+            fixture.bank.execute(&by_user, role_ix(20_000, false)).unwrap_err(),
+            program_error(ErrorCode::Unauthorized)
+        );
+
+        // 2x: 200 por hora
+        fixture.bank.execute(&set_role, role_ix(20_000, false)).unwrap();
+        let accounts = crate::accounts::ClaimTokens { claim_role: Some(claim_role), ..fixture.claim_accounts() };
+        fixture.claim_with(&accounts, fixture.args(200), 0).unwrap();
+        assert_eq!(
+            fixture.claim_with(&accounts, fixture.args(1), 1).unwrap_err(),
+            program_error(ErrorCode::InvalidPaymentAmount)
+        );
+
+        // Ilimitado: passa do limite diário (resta só max_claim_amount por claim)
+        fixture.bank.execute(&set_role, role_ix(0, true)).unwrap();
+        fixture.claim_with(&accounts, fixture.args(1_000_000), 1).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 1_000_200);
+    }
+
+    // Usuário na lista antes de ter a UserClaimAccount: a conta nasce com a
+    // flag falsa, mas a lista autoritativa continua bloqueando o claim
+    #[test]
+    fn blacklist_vec_blocks_claims_despite_a_desynced_flag() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let (blacklist, bump) = Pubkey::find_program_address(&[b"blacklist"], &crate::ID);
+        let list = BlacklistAccount { admin: fixture.admin, blacklisted_users: vec![fixture.claimer], bump };
+        fixture.bank.set(blacklist, runtime::TestAccount::anchor(&list));
+        assert!(!fixture.user_claim().is_blacklisted);
+
+        assert_eq!(
+            fixture.claim(fixture.args(100)).unwrap_err(),
+            program_error(ErrorCode::Unauthorized)
+        );
+
+        // Qualquer um pode reparar a flag a partir da lista
+        let sync = crate::accounts::SyncBlacklistFlag {
+            blacklist,
+            user_claim_account: fixture.user_claim_key(),
+            user: fixture.claimer,
+            config: config_key(),
+        };
+        fixture.bank.execute(&sync, crate::instruction::SyncBlacklistFlag {}).unwrap();
+        assert!(fixture.user_claim().is_blacklisted);
+        assert_eq!(runtime::events::<SecurityEvent>().last().unwrap().event_type, "BLACKLIST_FLAG_SYNCED");
+
+        // add_to_blacklist cria a conta de quem nunca fez claim já com a flag
+        let user = Pubkey::new_unique();
+        let (user_claim, user_claim_bump) = Pubkey::find_program_address(&[b"user_claim", user.as_ref()], &crate::ID);
+        let add = crate::accounts::AddToBlacklist {
+            admin: fixture.admin,
+            blacklist,
+            user_claim_account: user_claim,
+            blacklist_entry: None,
+            user,
+            config: config_key(),
+            system_program: system_program::ID,
+        };
+        fixture.bank.execute(&add, crate::instruction::AddToBlacklist { user, expires_at: 0 }).unwrap();
+        let account: UserClaimAccount = fixture.bank.read(&user_claim);
+        assert_eq!((account.user, account.bump, account.is_blacklisted), (user, user_claim_bump, true));
+        assert!(fixture.bank.read::<BlacklistAccount>(&blacklist).blacklisted_users.contains(&user));
+    }
+
+    #[test]
+    fn initialize_blacklist_rejects_a_spoofed_config() {
+        let admin = Pubkey::new_unique();
+        let attacker = Pubkey::new_unique();
+        runtime::start(1_000);
+        let mut bank = runtime::Bank::new();
+        bank.set(config_key(), config_account(admin, ConfigAccount::default()));
+        let spoofed = Pubkey::new_unique();
+        bank.set(spoofed, config_account(attacker, ConfigAccount::default()));
+        let (blacklist, bump) = Pubkey::find_program_address(&[b"blacklist"], &crate::ID);
+        let accounts = |admin, config| crate::accounts::InitializeBlacklist {
+            admin,
+            blacklist,
+            config,
+            system_program: system_program::ID,
+        };
+
+        assert_eq!(
+            bank.execute(&accounts(attacker, spoofed), crate::instruction::InitializeBlacklist {}).unwrap_err(),
+            ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintSeeds as u32)
+        );
+        assert_eq!(
+            bank.execute(&accounts(attacker, config_key()), crate::instruction::InitializeBlacklist {}).unwrap_err(),
+            program_error(ErrorCode::Unauthorized)
+        );
+        bank.execute(&accounts(admin, config_key()), crate::instruction::InitializeBlacklist {}).unwrap();
+        let list: BlacklistAccount = bank.read(&blacklist);
+        assert_eq!((list.admin, list.bump), (admin, bump));
+        assert!(list.blacklisted_users.is_empty());
+    }
+
+    // Claim de teste com config e conta de claim ajustadas antes da simulação
+    fn simulation_fixture(
+        config: impl FnOnce(&mut ConfigAccount),
+        user: impl FnOnce(&mut UserClaimAccount),
+    ) -> ClaimFixture {
+        let mut fixture = ClaimFixture::new(ConfigAccount { max_claim_per_user: 2_400, ..claim_config() });
+        let mut stored: ConfigAccount = fixture.bank.read(&config_key());
+        config(&mut stored);
+        fixture.bank.set(config_key(), runtime::TestAccount::anchor(&stored));
+        let mut user_claim = fixture.user_claim();
+        user(&mut user_claim);
+        fixture.bank.set(fixture.user_claim_key(), runtime::TestAccount::anchor(&user_claim));
+        fixture
+    }
+
+    // failing_check emitido por simulate_claim, conferindo que nada foi gravado
+    fn simulate(fixture: &mut ClaimFixture, amount: u64, timestamp: i64, category: u8) -> String {
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID).0;
+        let claimer = fixture.claimer.as_ref();
+        let accounts = crate::accounts::SimulateClaim {
+            claimer: fixture.claimer,
+            token_mint: fixture.mint,
+            user_claim_account: Some(fixture.user_claim_key()),
+            claim_role: None,
+            allowlist_entry: None,
+            user_registration: None,
+            blacklist: pda(&[b"blacklist"]),
+            blacklist_entry: pda(&[b"blacklist_entry", claimer]),
+            config: config_key(),
+        };
+        let before = (fixture.bank.get(&config_key()).data.clone(), fixture.user_claim().claim_nonce);
+        let ix = crate::instruction::SimulateClaim { amount, timestamp, category };
+        fixture.bank.execute(&accounts, ix).unwrap();
+        assert_eq!((fixture.bank.get(&config_key()).data.clone(), fixture.user_claim().claim_nonce), before);
+
+        let event = runtime::events::<ClaimSimulationEvent>().pop().unwrap();
+        assert_eq!(event.would_succeed, event.failing_check == "NONE");
+        event.failing_check
+    }
+
+    #[test]
+    fn simulate_claim_reports_each_failing_check() {
+        let check = |config: fn(&mut ConfigAccount), user: fn(&mut UserClaimAccount), amount, age, category| {
+            let mut fixture = simulation_fixture(config, user);
+            let timestamp = fixture.now - age;
+            simulate(&mut fixture, amount, timestamp, category)
+        };
+        // Relógio do ClaimFixture
+        const NOW: i64 = 10 * ONE_WEEK_SECONDS;
+
+        assert_eq!(check(|_| {}, |_| {}, 100, 0, 0), "NONE");
+        assert_eq!(check(|c| c.emergency_paused = true, |_| {}, 100, 0, 0), "PAUSED");
+        assert_eq!(check(|_| {}, |_| {}, 0, 0, 0), "INVALID_AMOUNT");
+        assert_eq!(check(|_| {}, |_| {}, 1_000_001, 0, 0), "AMOUNT_BOUNDS");
+        assert_eq!(check(|_| {}, |u| u.is_blacklisted = true, 100, 0, 0), "BLACKLISTED");
+        assert_eq!(check(|_| {}, |u| u.suspended_until = i64::MAX, 100, 0, 0), "SUSPENDED");
+        assert_eq!(check(|c| c.allowlist_enabled = true, |_| {}, 100, 0, 0), "NOT_ALLOWLISTED");
+        assert_eq!(check(|c| c.account_age_requirement_seconds = 1, |_| {}, 100, 0, 0), "ACCOUNT_TOO_YOUNG");
+        assert_eq!(check(|_| {}, |_| {}, 100, -1, 0), "SIGNATURE_FROM_FUTURE");
+        assert_eq!(check(|_| {}, |_| {}, 100, SIGNATURE_TOLERANCE_SECONDS + 1, 0), "EXPIRED_SIGNATURE");
+        let tripped = |c: &mut ConfigAccount| {
+            c.claims_per_minute_threshold = 1;
+            c.minute_window_start = NOW;
+            c.claims_in_current_minute = 1;
+        };
+        assert_eq!(check(tripped, |_| {}, 100, 0, 0), "CIRCUIT_BREAKER");
+        assert_eq!(check(|c| c.total_minted = c.total_supply_limit - 99, |_| {}, 100, 0, 0), "SUPPLY_LIMIT");
+        assert_eq!(check(|_| {}, |_| {}, 100, 0, 5), "INVALID_CATEGORY");
+        let full = |u: &mut UserClaimAccount| {
+            u.claim_history = vec![ClaimEntry { timestamp: NOW - 7_200, amount: 1, category: 0 }; MAX_CLAIM_HISTORY];
+        };
+        assert_eq!(check(|_| {}, full, 1, 0, 0), "CLAIM_HISTORY_FULL");
+        assert_eq!(check(|_| {}, |_| {}, 101, 0, 0), "HOURLY_LIMIT");
+        // 2.350 de 2.400 há duas horas: a janela horária está livre, a diária não
+        let earlier = |u: &mut UserClaimAccount| {
+            u.claim_history = vec![ClaimEntry { timestamp: NOW - 7_200, amount: 2_350, category: 0 }];
+        };
+        assert_eq!(check(|_| {}, earlier, 60, 0, 0), "DAILY_LIMIT");
+        let weekly = |c: &mut ConfigAccount| c.max_claim_per_week = 1_000;
+        let spent = |u: &mut UserClaimAccount| {
+            u.weekly_claimed = 1_000;
+            u.weekly_reset_timestamp = NOW;
+        };
+        assert_eq!(check(weekly, spent, 10, 0, 0), "WEEKLY_LIMIT");
+
+        // O supply do próprio mint, além do contabilizado pela config
+        let mut fixture = simulation_fixture(|c| c.enforce_mint_supply = true, |_| {});
+        let mint = anchor_spl::token_interface::spl_token_2022::state::Mint {
+            mint_authority: COption::Some(Pubkey::find_program_address(&[b"mint_authority"], &crate::ID).0),
+            supply: 1_000_000_000,
+            decimals: 9,
+            is_initialized: true,
+            ..Default::default()
+        };
+        fixture.bank.set(fixture.mint, runtime::TestAccount::packed(spl_token_2022::ID, mint));
+        assert_eq!(simulate(&mut fixture, 100, NOW, 0), "MINT_SUPPLY_LIMIT");
+    }
+
+    #[test]
+    fn claim_amount_bounds_are_inclusive() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let accounts = crate::accounts::UpdateConfig { admin: fixture.admin, config: config_key() };
+        let bounds = |min_claim_amount, max_claim_amount| crate::instruction::SetClaimAmountBounds {
+            min_claim_amount,
+            max_claim_amount,
+        };
+        assert_eq!(
+            fixture.bank.execute(&accounts, bounds(101, 100)).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
+        assert_eq!(
+            fixture.bank.execute(&accounts, bounds(0, 100)).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
+        fixture.bank.execute(&accounts, bounds(10, 100)).unwrap();
+
+        for amount in [9, 101] {
+            assert_eq!(
+                fixture.claim(fixture.args(amount)).unwrap_err(),
+                program_error(ErrorCode::InvalidPaymentAmount)
+            );
+        }
+        fixture.claim(fixture.args(10)).unwrap();
+        fixture.claim(fixture.args(100)).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 110);
+    }
+
+    #[test]
+    fn burn_description_is_capped_in_bytes() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(1_000)).unwrap();
+
+        // 'é' ocupa 2 bytes: 128 caracteres já somam MAX_DESC_LEN
+        let at_cap = "é".repeat(MAX_DESC_LEN / 2);
+        let ix = crate::instruction::BurnTokens { description: at_cap.clone(), ..fixture.burn_ix(10) };
+        fixture.burn(ix).unwrap();
+        assert_eq!(runtime::events::<TokenBurnEvent>().pop().unwrap().description, at_cap);
+
+        let over_cap = at_cap + "a";
+        assert_eq!(over_cap.chars().count(), MAX_DESC_LEN / 2 + 1);
+        let ix = crate::instruction::BurnTokens { description: over_cap, ..fixture.burn_ix(10) };
+        assert_eq!(fixture.burn(ix).unwrap_err(), program_error(ErrorCode::DescriptionTooLong));
+        assert_eq!(fixture.balance(&fixture.claimer), 990);
+    }
+
+    #[test]
+    fn pause_reason_is_readable_until_resume() {
+        let admin = Pubkey::new_unique();
+        let now = 1_000_000;
+        runtime::start(now);
+        let mut bank = runtime::Bank::new();
+        bank.set(config_key(), config_account(admin, ConfigAccount::default()));
+        let accounts = crate::accounts::EmergencyPause { admin, config: config_key() };
+
+        let too_long = "x".repeat(MAX_PAUSE_REASON_LEN + 1);
+        let pause = |reason: &str| crate::instruction::EmergencyPause { reason: reason.to_string(), duration_secs: 0 };
+        assert_eq!(
+            bank.execute(&accounts, pause(&too_long)).unwrap_err(),
+            program_error(ErrorCode::PauseReasonTooLong)
+        );
+        bank.execute(&accounts, pause(&too_long[1..])).unwrap();
+        let config: ConfigAccount = bank.read(&config_key());
+        assert_eq!((config.pause_reason.len(), config.paused_at), (MAX_PAUSE_REASON_LEN, now));
+
+        bank.execute(&accounts, pause("oracle incident")).unwrap();
+        let config: ConfigAccount = bank.read(&config_key());
+        assert_eq!((config.pause_reason.as_str(), config.paused_at), ("oracle incident", now));
+
+        runtime::set_now(now + 10);
+        bank.execute(&accounts, crate::instruction::EmergencyResume {}).unwrap();
+        let config: ConfigAccount = bank.read(&config_key());
+        assert!(!config.is_paused(now + 10));
+        assert_eq!((config.pause_reason.as_str(), config.paused_at), ("", 0));
+    }
+
+    #[test]
+    fn guardians_swap_the_admin_only_with_the_threshold() {
+        use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+        use anchor_lang::InstructionData;
+
+        let admin = Pubkey::new_unique();
+        let new_admin = Pubkey::new_unique();
+        let guardians = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        runtime::start(1_000);
+        let mut bank = runtime::Bank::new();
+        bank.set(config_key(), config_account(admin, ConfigAccount::default()));
+        let update = crate::accounts::UpdateConfig { admin, config: config_key() };
+        let set = crate::instruction::SetGuardians { guardians: guardians.clone(), guardian_threshold: 2 };
+        bank.execute(&update, set).unwrap();
+
+        // Guardians (e intrusos) chegam por remaining_accounts
+        let mut transfer = |signers: &[(Pubkey, bool)]| {
+            let mut metas = crate::accounts::TransferAdminEmergency { config: config_key() }.to_account_metas(None);
+            metas.extend(signers.iter().map(|&(key, is_signer)| AccountMeta::new_readonly(key, is_signer)));
+            let ix = crate::instruction::TransferAdminEmergency { new_admin };
+            bank.process(Instruction::new_with_bytes(crate::ID, &ix.data(), metas))
+        };
+        let insufficient = program_error(ErrorCode::InsufficientGuardianSignatures);
+        assert_eq!(transfer(&[(guardians[0], true)]).unwrap_err(), insufficient);
+        assert_eq!(transfer(&[(guardians[0], true), (guardians[0], true)]).unwrap_err(), insufficient);
+        assert_eq!(transfer(&[(guardians[0], true), (Pubkey::new_unique(), true)]).unwrap_err(), insufficient);
+        assert_eq!(transfer(&[(guardians[0], true), (guardians[1], false)]).unwrap_err(), insufficient);
+        transfer(&[(guardians[0], true), (guardians[2], true)]).unwrap();
+
+        assert_eq!(bank.read::<ConfigAccount>(&config_key()).admin, new_admin);
+        let event = runtime::events::<SecurityEvent>().pop().unwrap();
+        assert_eq!((event.event_type.as_str(), event.user), ("EMERGENCY_ADMIN_TRANSFER", new_admin));
+    }
+
+    #[test]
+    fn delegated_burn_signature_is_bound_to_the_delegate() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(100)).unwrap();
+        let (owner, ata) = (fixture.claimer, fixture.ata(&fixture.claimer));
+        let delegate = Pubkey::new_unique();
+        fixture.bank.set(delegate, runtime::TestAccount::wallet());
+        let approve = spl_token_2022::instruction::approve(&spl_token_2022::ID, &ata, &delegate, &owner, &[], 50).unwrap();
+        fixture.bank.process(approve).unwrap();
+
+        let accounts = crate::accounts::BurnFromDelegate {
+            delegate,
+            owner,
+            payment_token_mint: fixture.mint,
+            owner_token_account: ata,
+            user_burn_account: fixture.user_burn_key(),
+            registered_token: None,
+            backend_authority: SIGNER,
+            stats: None,
+            config: config_key(),
+            sysvar_instructions: sysvar_instructions::ID,
+            token_program: fixture.token_program,
+            system_program: system_program::ID,
+        };
+        let (mint, now, signature) = (fixture.mint, fixture.now, [7u8; 64]);
+        let ix = || crate::instruction::BurnFromDelegate {
+            amount: 10,
+            timestamp: now,
+            signature,
+            description: "settlement".to_string(),
+        };
+
+        // O backend autorizou outro delegate: a aprovação SPL não basta
+        let other = Pubkey::new_unique();
+        fixture.sign(&delegated_burn_message(&owner, &other, &mint, 10, now, 0), &signature);
+        assert_eq!(
+            fixture.bank.execute(&accounts, ix()).unwrap_err(),
+            program_error(ErrorCode::InvalidSignature)
+        );
+        // Nem uma assinatura de burn_tokens do próprio dono
+        let message = burn_message(&owner, &mint, 10, now, 0, BURN_REASON_UNSPECIFIED, 0);
+        fixture.sign(&message, &signature);
+        assert_eq!(
+            fixture.bank.execute(&accounts, ix()).unwrap_err(),
+            program_error(ErrorCode::InvalidSignature)
+        );
+        assert_eq!(fixture.balance(&owner), 100);
+
+        fixture.sign(&delegated_burn_message(&owner, &delegate, &mint, 10, now, 0), &signature);
+        fixture.bank.execute(&accounts, ix()).unwrap();
+        assert_eq!(fixture.balance(&owner), 90);
+    }
+
+    #[test]
+    fn vouchers_outlive_the_freshness_window_until_expiry() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let issued_at = fixture.now;
+        let voucher = ClaimArgs { expiry_ts: issued_at + ONE_WEEK_SECONDS, ..fixture.args(100) };
+        let plain = fixture.args(100);
+
+        // Três dias depois o payload comum expirou, o voucher não
+        fixture.set_now(issued_at + 3 * ONE_DAY_SECONDS);
+        assert_eq!(fixture.claim(plain).unwrap_err(), program_error(ErrorCode::ExpiredSignature));
+        fixture.claim(voucher).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 100);
+
+        // Vale até expiry_ts inclusive
+        let last = ClaimArgs { timestamp: issued_at, expiry_ts: issued_at + ONE_WEEK_SECONDS, ..fixture.args(50) };
+        fixture.set_now(issued_at + ONE_WEEK_SECONDS + 1);
+        assert_eq!(fixture.claim(last.clone()).unwrap_err(), program_error(ErrorCode::VoucherExpired));
+        fixture.set_now(issued_at + ONE_WEEK_SECONDS);
+        fixture.claim(last).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 150);
+    }
+
+    #[test]
+    fn treasury_takes_deposits_and_pays_out_only_after_the_timelock() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(1_000)).unwrap();
+        let (treasury_authority, treasury_bump) = Pubkey::find_program_address(&[b"treasury"], &crate::ID);
+        let treasury = fixture.ata(&treasury_authority);
+        let initialize = crate::accounts::InitializeTreasury {
+            admin: fixture.admin,
+            token_mint: fixture.mint,
+            treasury_authority,
+            treasury_token_account: treasury,
+            config: config_key(),
+            token_program: fixture.token_program,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+        };
+        let by_user = crate::accounts::InitializeTreasury { admin: fixture.claimer, ..initialize };
+        assert_eq!(
+            fixture.bank.execute(&by_user, crate::instruction::InitializeTreasury {}).unwrap_err(),
+            program_error(ErrorCode::Unauthorized)
+        );
+        fixture.bank.execute(&initialize, crate::instruction::InitializeTreasury {}).unwrap();
+        assert_eq!(fixture.bank.read::<ConfigAccount>(&config_key()).treasury_bump, treasury_bump);
+        assert!(fixture.bank.get(&treasury_authority).lamports >= Rent::default().minimum_balance(0));
+
+        let deposit = crate::accounts::DepositToTreasury {
+            depositor: fixture.claimer,
+            token_mint: fixture.mint,
+            depositor_token_account: fixture.ata(&fixture.claimer),
+            treasury_authority,
+            treasury_token_account: treasury,
+            config: config_key(),
+            token_program: fixture.token_program,
+        };
+        fixture.bank.execute(&deposit, crate::instruction::DepositToTreasury { amount: 400 }).unwrap();
+        assert_eq!((fixture.balance(&fixture.claimer), fixture.balance(&treasury_authority)), (600, 400));
+
+        // Saque só para a carteira da ação pendente, depois de 24h
+        let destination = Pubkey::new_unique();
+        fixture.create_ata(&destination);
+        fixture.request_action(AdminActionType::EmergencyWithdraw, destination, 0).unwrap();
+        let withdraw = |fixture: &ClaimFixture, owner| crate::accounts::EmergencyWithdraw {
+            admin: fixture.admin,
+            pending_action: fixture.pending_action_key(),
+            token_mint: fixture.mint,
+            treasury_authority,
+            treasury_token_account: treasury,
+            destination_token_account: fixture.ata(&owner),
+            config: config_key(),
+            token_program: fixture.token_program,
+        };
+        let accounts = withdraw(&fixture, destination);
+        assert_eq!(
+            fixture.bank.execute(&accounts, crate::instruction::EmergencyWithdraw { amount: 300 }).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
+        fixture.set_now(fixture.now + ADMIN_ACTION_DELAY_SECONDS);
+        let accounts = withdraw(&fixture, fixture.claimer);
+        assert_eq!(
+            fixture.bank.execute(&accounts, crate::instruction::EmergencyWithdraw { amount: 300 }).unwrap_err(),
+            program_error(ErrorCode::InvalidTokenAccount)
+        );
+        let accounts = withdraw(&fixture, destination);
+        fixture.bank.execute(&accounts, crate::instruction::EmergencyWithdraw { amount: 300 }).unwrap();
+        assert_eq!((fixture.balance(&treasury_authority), fixture.balance(&destination)), (100, 300));
+        // A ação pendente é fechada: não dá para sacar de novo com ela
+        assert_eq!(fixture.bank.get(&fixture.pending_action_key()).owner, system_program::ID);
+    }
+
+    // Token Metadata mínimo: CreateMetadataAccountV3 exige a assinatura da
+    // mint authority, cria a conta no PDA do mint e grava os campos que o
+    // Metaplex gravaria (sem o padding de nome, símbolo e URI)
+    fn token_metadata_stub(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+        use anchor_lang::solana_program::program::invoke_signed;
+        use anchor_lang::solana_program::system_instruction;
+        use anchor_spl::metadata::mpl_token_metadata::{
+            accounts::Metadata as MetadataAccount, instructions::CreateMetadataAccountV3InstructionArgs,
+            types::Key, ID as METADATA_ID,
+        };
+        use anchor_spl::token_interface::spl_token_2022::{extension::StateWithExtensions, state::Mint};
+        use borsh09::{BorshDeserialize, BorshSerialize};
+
+        let [metadata, mint, mint_authority, payer, update_authority, system, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let Some((33, args)) = data.split_first() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        let args = CreateMetadataAccountV3InstructionArgs::try_from_slice(args)?;
+        let (expected, bump) = MetadataAccount::find_pda(mint.key);
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint.try_borrow_data()?)?.base;
+        if *metadata.key != expected
+            || !mint_authority.is_signer
+            || mint_state.mint_authority != COption::Some(*mint_authority.key)
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
 
-    #[account(
-        init_if_needed,
-        payer = claimer,
-        associated_token::mint = token_mint,
-        associated_token::authority = claimer,
-    )]
-    pub claimer_token_account: Account<'info, TokenAccount>,
+        let account = MetadataAccount {
+            key: Key::MetadataV1,
+            update_authority: *update_authority.key,
+            mint: *mint.key,
+            name: args.data.name,
+            symbol: args.data.symbol,
+            uri: args.data.uri,
+            seller_fee_basis_points: args.data.seller_fee_basis_points,
+            creators: args.data.creators,
+            primary_sale_happened: false,
+            is_mutable: args.is_mutable,
+            edition_nonce: None,
+            token_standard: None,
+            collection: args.data.collection,
+            uses: args.data.uses,
+            collection_details: args.collection_details,
+            programmable_config: None,
+        };
+        let bytes = account.try_to_vec()?;
+        let create = system_instruction::create_account(
+            payer.key,
+            metadata.key,
+            Rent::default().minimum_balance(bytes.len()),
+            bytes.len() as u64,
+            &METADATA_ID,
+        );
+        invoke_signed(
+            &create,
+            &[payer.clone(), metadata.clone(), system.clone()],
+            &[&[b"metadata", METADATA_ID.as_ref(), mint.key.as_ref(), &[bump]]],
+        )?;
+        metadata.try_borrow_mut_data()?.copy_from_slice(&bytes);
+        Ok(())
+    }
 
-    #[account(
-        init_if_needed,
-        payer = claimer,
-        space = 8 + 32 + 8 + 8 + 8 + 8, // discriminator + user + total_claimed + last_claim_timestamp + daily_claimed + daily_reset_timestamp
-        seeds = [b"user_claim", claimer.key().as_ref()],
-        bump,
-    )]
-    pub user_claim_account: Account<'info, UserClaimAccount>,
+    #[test]
+    fn create_token_metadata_names_the_mint_authority_as_update_authority() {
+        use anchor_spl::metadata::mpl_token_metadata::{accounts::Metadata as MetadataAccount, ID as METADATA_ID};
+
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.bank.load(METADATA_ID, token_metadata_stub);
+        let rent = Rent::default();
+        let mut rent_data = rent.lamports_per_byte_year.to_le_bytes().to_vec();
+        rent_data.extend(rent.exemption_threshold.to_le_bytes());
+        rent_data.push(rent.burn_percent);
+        fixture.bank.set(
+            anchor_lang::solana_program::sysvar::rent::ID,
+            runtime::TestAccount::new(anchor_lang::solana_program::sysvar::ID, rent_data),
+        );
 
-    /// CHECK: This is the backend authority account
-    pub backend_authority: UncheckedAccount<'info>,
+        let mint_authority = Pubkey::find_program_address(&[b"mint_authority"], &crate::ID).0;
+        let metadata = MetadataAccount::find_pda(&fixture.mint).0;
+        let accounts = crate::accounts::CreateTokenMetadata {
+            admin: fixture.admin,
+            token_mint: fixture.mint,
+            mint_authority,
+            metadata,
+            config: config_key(),
+            token_metadata_program: METADATA_ID,
+            system_program: system_program::ID,
+            rent: anchor_lang::solana_program::sysvar::rent::ID,
+        };
+        let ix = |name: &str, symbol: &str, uri: &str| crate::instruction::CreateTokenMetadata {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            uri: uri.to_string(),
+        };
+        let uri = "https://example.com/torque.json";
+
+        let by_user = crate::accounts::CreateTokenMetadata { admin: fixture.claimer, ..accounts };
+        assert_eq!(
+            fixture.bank.execute(&by_user, ix("Torque", "TRQ", uri)).unwrap_err(),
+            program_error(ErrorCode::Unauthorized)
+        );
+        let long_name = "n".repeat(MAX_METADATA_NAME_LEN + 1);
+        let long_symbol = "S".repeat(MAX_METADATA_SYMBOL_LEN + 1);
+        let long_uri = "u".repeat(MAX_METADATA_URI_LEN + 1);
+        for invalid in [
+            ix(&long_name, "TRQ", uri),
+            ix("", "TRQ", uri),
+            ix("Torque", &long_symbol, uri),
+            ix("Torque", "", uri),
+            ix("Torque", "TRQ", &long_uri),
+        ] {
+            assert_eq!(
+                fixture.bank.execute(&accounts, invalid).unwrap_err(),
+                program_error(ErrorCode::InvalidMetadata)
+            );
+        }
 
-    /// CHECK: Mint authority PDA
-    #[account(
-        seeds = [b"mint_authority"],
-        bump,
-    )]
-    pub mint_authority: UncheckedAccount<'info>,
+        fixture.bank.execute(&accounts, ix("Torque", "TRQ", uri)).unwrap();
+        let stored = fixture.bank.get(&metadata);
+        assert_eq!(stored.owner, METADATA_ID);
+        let stored = MetadataAccount::from_bytes(&stored.data).unwrap();
+        assert_eq!((stored.name.as_str(), stored.symbol.as_str(), stored.uri.as_str()), ("Torque", "TRQ", uri));
+        assert_eq!((stored.mint, stored.update_authority), (fixture.mint, mint_authority));
+        assert!(stored.is_mutable);
+    }
 
-    #[account(
-        mut,
-        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
-    )]
-    pub config: Account<'info, ConfigAccount>,
+    #[test]
+    fn future_dated_claims_need_the_admin_opt_in() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let future = ClaimArgs { timestamp: fixture.now + 60, ..fixture.args(100) };
+        assert_eq!(
+            fixture.claim(future.clone()).unwrap_err(),
+            program_error(ErrorCode::SignatureFromFuture)
+        );
 
-    /// CHECK: This is the Solana Instructions Sysvar Account for signature verification
-    #[account(address = sysvar_instructions::ID)]
-    pub sysvar_instructions: AccountInfo<'info>,
+        let accounts = crate::accounts::UpdateConfig { admin: fixture.admin, config: config_key() };
+        let allow = |allow| crate::instruction::SetAllowFutureTimestamps { allow };
+        let by_user = crate::accounts::UpdateConfig { admin: fixture.claimer, ..accounts };
+        assert_eq!(
+            fixture.bank.execute(&by_user, allow(true)).unwrap_err(),
+            program_error(ErrorCode::Unauthorized)
+        );
+        fixture.bank.execute(&accounts, allow(true)).unwrap();
+        fixture.claim(future).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 100);
+    }
 
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-}
+    #[test]
+    fn sliding_window_blocks_the_midnight_double_claim() {
+        // 2.400 por dia => 100 por hora
+        let mut fixture = ClaimFixture::new(ConfigAccount { max_claim_per_user: 2_400, ..claim_config() });
+        let midnight = fixture.now + ONE_DAY_SECONDS;
+        let first = midnight - ONE_DAY_SECONDS + ONE_HOUR_SECONDS - 60;
+
+        // 100 aos 59 minutos de cada hora, o último às 23:59
+        for hour in 0..24 {
+            fixture.set_now(first + hour * ONE_HOUR_SECONDS);
+            fixture.claim(fixture.args(100)).unwrap();
+        }
+        assert_eq!(fixture.now, midnight - 60);
 
-#[derive(Accounts)]
-pub struct InitializeConfig<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+        // 00:01: uma janela fixa teria zerado, a deslizante ainda soma 2.400
+        fixture.set_now(midnight + 60);
+        assert_eq!(
+            fixture.claim(fixture.args(100)).unwrap_err(),
+            program_error(ErrorCode::InvalidPaymentAmount)
+        );
 
-    #[account(
-        init,
-        payer = admin,
-        space = 8 + 32 + 32 + 1 + 8 + 8 + 8, // discriminator + payment_token_mint + admin + emergency_paused + max_claim_per_user + total_supply_limit + total_minted
-    )]
-    pub config: Account<'info, ConfigAccount>,
+        // Só libera o que sai das últimas 24h
+        fixture.set_now(first + ONE_DAY_SECONDS);
+        assert_eq!(
+            fixture.claim(fixture.args(101)).unwrap_err(),
+            program_error(ErrorCode::InvalidPaymentAmount)
+        );
+        fixture.claim(fixture.args(100)).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 2_500);
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    #[test]
+    fn claim_categories_have_independent_allowances() {
+        let mut fixture = ClaimFixture::new(ConfigAccount { max_claim_per_user: 2_400, ..claim_config() });
+        let accounts = crate::accounts::UpdateConfig { admin: fixture.admin, config: config_key() };
+        let cap = |category, daily_cap| crate::instruction::SetCategoryDailyCap { category, daily_cap };
+        assert_eq!(
+            fixture.bank.execute(&accounts, cap(DEFAULT_CLAIM_CATEGORY, 1)).unwrap_err(),
+            program_error(ErrorCode::InvalidClaimCategory)
+        );
+        fixture.bank.execute(&accounts, cap(1, 5_000)).unwrap();
+        fixture.bank.execute(&accounts, cap(2, 300)).unwrap();
+        let claim = |fixture: &mut ClaimFixture, category, amount| {
+            fixture.claim(ClaimArgs { category, ..fixture.args(amount) })
+        };
+
+        // Um prêmio grande esgota só a própria categoria
+        claim(&mut fixture, 1, 5_000).unwrap();
+        assert_eq!(claim(&mut fixture, 1, 1).unwrap_err(), program_error(ErrorCode::InvalidPaymentAmount));
+        claim(&mut fixture, DEFAULT_CLAIM_CATEGORY, 100).unwrap();
+        claim(&mut fixture, 2, 300).unwrap();
+        let event = runtime::events::<ClaimLimitsEvent>().pop().unwrap();
+        assert_eq!((event.category, event.daily_claimed, event.daily_remaining), (2, 300, 0));
+        assert_eq!(
+            claim(&mut fixture, DEFAULT_CLAIM_CATEGORY, 1).unwrap_err(),
+            program_error(ErrorCode::InvalidPaymentAmount)
+        );
 
-#[derive(Accounts)]
-pub struct BurnTokens<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
+        // Categoria sem limite configurado está desativada
+        assert_eq!(claim(&mut fixture, 3, 1).unwrap_err(), program_error(ErrorCode::InvalidClaimCategory));
+        assert_eq!(fixture.balance(&fixture.claimer), 5_400);
+    }
 
-    #[account(mut)]
-    pub payment_token_mint: Account<'info, Mint>,
+    #[test]
+    fn blacklist_rejects_additions_past_its_capacity() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let (blacklist, bump) = Pubkey::find_program_address(&[b"blacklist"], &crate::ID);
+        let users: Vec<Pubkey> = (0..MAX_BLACKLIST_SIZE - 1).map(|_| Pubkey::new_unique()).collect();
+        let list = BlacklistAccount { admin: fixture.admin, blacklisted_users: users.clone(), bump };
+        fixture.bank.set(blacklist, runtime::TestAccount::anchor(&list));
+        let mut add = |user: Pubkey| {
+            let accounts = crate::accounts::AddToBlacklist {
+                admin: fixture.admin,
+                blacklist,
+                user_claim_account: Pubkey::find_program_address(&[b"user_claim", user.as_ref()], &crate::ID).0,
+                blacklist_entry: None,
+                user,
+                config: config_key(),
+                system_program: system_program::ID,
+            };
+            fixture.bank.execute(&accounts, crate::instruction::AddToBlacklist { user, expires_at: 0 })
+        };
+
+        add(Pubkey::new_unique()).unwrap();
+        assert_eq!(add(Pubkey::new_unique()).unwrap_err(), program_error(ErrorCode::BlacklistFull));
+        // Quem já está na lista cheia só tem a flag reafirmada
+        add(users[0]).unwrap();
+        assert_eq!(fixture.bank.read::<BlacklistAccount>(&blacklist).blacklisted_users.len(), MAX_BLACKLIST_SIZE);
+    }
 
-    #[account(
-        mut,
-        associated_token::mint = payment_token_mint,
-        associated_token::authority = payer,
-    )]
-    pub payer_payment_token_account: Account<'info, TokenAccount>,
+    #[test]
+    fn admin_mint_cap_is_timelocked_and_inclusive() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.request_action(AdminActionType::SetAdminMintCap, Pubkey::default(), 1_000).unwrap();
+        assert_eq!(fixture.execute_action().unwrap_err(), program_error(ErrorCode::InvalidInput));
+        fixture.set_now(fixture.now + ADMIN_ACTION_DELAY_SECONDS);
+        fixture.execute_action().unwrap();
+        assert_eq!(fixture.bank.read::<ConfigAccount>(&config_key()).max_admin_mint_per_call, 1_000);
+
+        let recipient = fixture.claimer;
+        let accounts = fixture.mint_accounts(recipient);
+        assert_eq!(
+            fixture.bank.execute(&accounts, crate::instruction::MintTokens { amount: 1_001, recipient }).unwrap_err(),
+            program_error(ErrorCode::AdminMintTooLarge)
+        );
+        fixture.bank.execute(&accounts, crate::instruction::MintTokens { amount: 1_000, recipient }).unwrap();
+        assert_eq!(fixture.balance(&recipient), 1_000);
+    }
 
-    /// CHECK: This is the backend authority account
-    pub backend_authority: UncheckedAccount<'info>,
+    #[test]
+    fn close_config_returns_the_rent_to_the_admin() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let accounts = crate::accounts::CloseConfig {
+            admin: fixture.admin,
+            pending_action: None,
+            config: config_key(),
+        };
+        let close = |force, confirm_no_dependents| {
+            crate::instruction::CloseConfig { force, confirm_no_dependents }
+        };
+        let by_user = crate::accounts::CloseConfig { admin: fixture.claimer, ..accounts };
+        assert_eq!(
+            fixture.bank.execute(&by_user, close(false, true)).unwrap_err(),
+            program_error(ErrorCode::Unauthorized)
+        );
+        assert_eq!(
+            fixture.bank.execute(&accounts, close(false, false)).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
 
-    #[account(
-        constraint = config.payment_token_mint != Pubkey::default()
-            @ ErrorCode::PaymentTokenNotConfigured,
-    )]
-    pub config: Account<'info, ConfigAccount>,
+        // Config nova (nada mintado): fecha na hora
+        fixture.bank.set(fixture.admin, runtime::TestAccount::wallet());
+        let admin_before = fixture.bank.get(&fixture.admin).lamports;
+        let rent = fixture.bank.get(&config_key()).lamports;
+        assert!(rent > 0);
+        fixture.bank.execute(&accounts, close(false, true)).unwrap();
+        let config = fixture.bank.get(&config_key());
+        assert_eq!((config.lamports, config.owner), (0, system_program::ID));
+        assert_eq!(fixture.bank.get(&fixture.admin).lamports, admin_before + rent);
+    }
 
-    /// CHECK: This is the Solana Instructions Sysvar Account for signature verification
-    #[account(address = sysvar_instructions::ID)]
-    pub sysvar_instructions: AccountInfo<'info>,
+    #[test]
+    fn close_config_after_mints_needs_the_timelocked_force() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(100)).unwrap();
+        let close = |force| crate::instruction::CloseConfig { force, confirm_no_dependents: true };
+        let accounts = crate::accounts::CloseConfig {
+            admin: fixture.admin,
+            pending_action: Some(fixture.pending_action_key()),
+            config: config_key(),
+        };
+        let without_action = crate::accounts::CloseConfig { pending_action: None, ..accounts };
+        assert_eq!(
+            fixture.bank.execute(&without_action, close(false)).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
+        assert_eq!(
+            fixture.bank.execute(&without_action, close(true)).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
 
-    pub token_program: Program<'info, Token>,
-}
+        fixture.request_action(AdminActionType::CloseConfig, Pubkey::default(), 0).unwrap();
+        assert_eq!(
+            fixture.bank.execute(&accounts, close(true)).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
+        fixture.set_now(fixture.now + ADMIN_ACTION_DELAY_SECONDS);
+        fixture.bank.execute(&accounts, close(true)).unwrap();
+        assert_eq!(fixture.bank.get(&config_key()).owner, system_program::ID);
+        assert_eq!(fixture.bank.get(&fixture.pending_action_key()).owner, system_program::ID);
+    }
 
-#[derive(Accounts)]
-pub struct MintTokens<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    #[test]
+    fn verbose_logging_only_changes_the_logs() {
+        // Mesmo fluxo com os logs decorativos ligados e desligados
+        let run = |verbose_logging| {
+            let mut fixture = ClaimFixture::new(claim_config());
+            let accounts = crate::accounts::UpdateConfig { admin: fixture.admin, config: config_key() };
+            fixture.bank.execute(&accounts, crate::instruction::SetVerboseLogging { verbose_logging }).unwrap();
+            fixture.claim(fixture.args(1_000)).unwrap();
+            fixture.burn(fixture.burn_ix(300)).unwrap();
+            assert_eq!(
+                fixture.claim(fixture.args(0)).unwrap_err(),
+                program_error(ErrorCode::InvalidPaymentAmount)
+            );
+
+            let config: ConfigAccount = fixture.bank.read(&config_key());
+            let user_claim = fixture.user_claim();
+            let state = (
+                fixture.balance(&fixture.claimer),
+                config.total_minted,
+                config.global_seq,
+                user_claim.claim_nonce,
+                user_claim.total_claimed,
+                runtime::events::<TokenClaimEvent>().len(),
+                runtime::events::<TokenBurnEvent>().len(),
+            );
+            (state, runtime::logs().len())
+        };
+
+        let (quiet, quiet_logs) = run(false);
+        let (verbose, verbose_logs) = run(true);
+        assert_eq!(quiet, verbose);
+        assert_eq!(quiet.0, 700);
+        if cfg!(not(feature = "structured-logs")) {
+            assert!(quiet_logs < verbose_logs);
+        }
+    }
 
-    #[account(mut)]
-    pub token_mint: Account<'info, Mint>,
+    #[test]
+    fn custom_daily_limits_replace_the_default_both_ways() {
+        // 2.400 por dia => 100 por hora; o limite próprio também vale por dia/24
+        let set_limit = |fixture: &mut ClaimFixture, custom_daily_limit, pending_action| {
+            let accounts = crate::accounts::SetUserLimit {
+                admin: fixture.admin,
+                user: fixture.claimer,
+                registered_token: None,
+                user_claim_account: fixture.user_claim_key(),
+                pending_action,
+                config: config_key(),
+                system_program: system_program::ID,
+            };
+            fixture.bank.execute(&accounts, crate::instruction::SetUserLimit { custom_daily_limit })
+        };
+
+        // Maior que o padrão: VIP com 24.000 por dia (1.000 por hora)
+        let mut vip = ClaimFixture::new(ConfigAccount { max_claim_per_user: 2_400, ..claim_config() });
+        set_limit(&mut vip, Some(24_000), None).unwrap();
+        vip.claim(vip.args(1_000)).unwrap();
+        assert_eq!(vip.claim(vip.args(1)).unwrap_err(), program_error(ErrorCode::InvalidPaymentAmount));
+
+        // Acima de 10x o padrão só com a ação pendente de 24h para este valor
+        assert_eq!(
+            set_limit(&mut vip, Some(24_001), None).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
+        let user = vip.claimer;
+        vip.request_action(AdminActionType::SetUserLimit, user, 24_001).unwrap();
+        vip.set_now(vip.now + ADMIN_ACTION_DELAY_SECONDS);
+        let pending_action = Some(vip.pending_action_key());
+        set_limit(&mut vip, Some(24_001), pending_action).unwrap();
+        assert_eq!(vip.user_claim().custom_daily_limit, Some(24_001));
+
+        // Menor que o padrão: 240 por dia (10 por hora), e None volta ao padrão
+        let mut limited = ClaimFixture::new(ConfigAccount { max_claim_per_user: 2_400, ..claim_config() });
+        set_limit(&mut limited, Some(240), None).unwrap();
+        assert_eq!(
+            limited.claim(limited.args(11)).unwrap_err(),
+            program_error(ErrorCode::InvalidPaymentAmount)
+        );
+        limited.claim(limited.args(10)).unwrap();
+        set_limit(&mut limited, None, None).unwrap();
+        limited.claim(limited.args(90)).unwrap();
+        assert_eq!(limited.balance(&limited.claimer), 100);
+    }
 
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = recipient,
-    )]
-    pub recipient_token_account: Account<'info, TokenAccount>,
+    #[test]
+    fn initialize_config_captures_decimals_and_flags_odd_supply_limits() {
+        use anchor_spl::token_interface::spl_token_2022::state::Mint;
+
+        // Config inicializada com um mint de 9 decimals; retorna os SecurityEvent
+        let initialize = |total_supply_limit| {
+            runtime::start(1_000);
+            let mut bank = runtime::Bank::new();
+            let (admin, token_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+            let mint_authority = Pubkey::find_program_address(&[b"mint_authority"], &crate::ID).0;
+            let mint = Mint {
+                mint_authority: COption::Some(mint_authority),
+                decimals: 9,
+                is_initialized: true,
+                ..Mint::default()
+            };
+            bank.set(token_mint, runtime::TestAccount::packed(spl_token_2022::ID, mint));
+            let accounts = crate::accounts::InitializeConfig {
+                admin,
+                token_mint,
+                config: config_key(),
+                mint_authority,
+                system_program: system_program::ID,
+            };
+            let ix = crate::instruction::InitializeConfig {
+                payment_token_mint: token_mint,
+                max_claim_per_user: 1_000,
+                total_supply_limit,
+                backend_authority: Pubkey::new_unique(),
+                guardian: Pubkey::new_unique(),
+                mint_authority,
+            };
+            bank.execute(&accounts, ix).unwrap();
+            let config: ConfigAccount = bank.read(&config_key());
+            assert_eq!(config.decimals, 9);
+            assert_eq!(config.to_base_units(3).unwrap(), 3_000_000_000);
+            assert_eq!(config.from_base_units(3_999_999_999).unwrap(), 3);
+            runtime::events::<SecurityEvent>()
+        };
+
+        // 21M tokens inteiros: nada a avisar
+        assert!(initialize(21_000_000 * 1_000_000_000).is_empty());
+        // Menos de 1 token, ou fração de token: provavelmente decimals esquecidos
+        for suspicious in [21_000_000, 1_500_000_000] {
+            let events = initialize(suspicious);
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].event_type, "SUSPICIOUS_SUPPLY_LIMIT");
+        }
+    }
 
-    /// CHECK: Conta do destinatário dos tokens
-    pub recipient: UncheckedAccount<'info>,
+    #[test]
+    fn blacklist_batches_dedupe_sync_flags_and_cap_their_size() {
+        use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+        use anchor_lang::InstructionData;
+
+        let mut fixture = ClaimFixture::new(claim_config());
+        let (blacklist, bump) = Pubkey::find_program_address(&[b"blacklist"], &crate::ID);
+        let list = BlacklistAccount { admin: fixture.admin, blacklisted_users: Vec::new(), bump };
+        fixture.bank.set(blacklist, runtime::TestAccount::anchor(&list));
+        let accounts = crate::accounts::ManageBlacklistBatch {
+            admin: fixture.admin,
+            blacklist,
+            config: config_key(),
+        };
+        // UserClaimAccount a sincronizar chegam por remaining_accounts
+        let batch = |fixture: &mut ClaimFixture, data: Vec<u8>, user_claims: &[Pubkey]| {
+            let mut metas = accounts.to_account_metas(None);
+            metas.extend(user_claims.iter().map(|key| AccountMeta::new(*key, false)));
+            fixture.bank.process(Instruction::new_with_bytes(crate::ID, &data, metas))
+        };
+        let add = |users: Vec<Pubkey>| crate::instruction::AddToBlacklistBatch { users }.data();
+        let remove = |users: Vec<Pubkey>| crate::instruction::RemoveFromBlacklistBatch { users }.data();
+
+        let too_many: Vec<Pubkey> = (0..=MAX_BLACKLIST_BATCH_SIZE).map(|_| Pubkey::new_unique()).collect();
+        assert_eq!(
+            batch(&mut fixture, add(too_many), &[]).unwrap_err(),
+            program_error(ErrorCode::BatchTooLarge)
+        );
+        assert_eq!(
+            batch(&mut fixture, add(Vec::new()), &[]).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
+        // Uma conta de claim de quem não está no lote é rejeitada
+        let other = Pubkey::new_unique();
+        let user_claim = fixture.user_claim_key();
+        assert_eq!(
+            batch(&mut fixture, add(vec![other]), &[user_claim]).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
 
-    #[account(
-        mut,
-        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
-        constraint = config.payment_token_mint == token_mint.key() @ ErrorCode::InvalidPaymentToken,
-    )]
-    pub config: Account<'info, ConfigAccount>,
+        // Repetidos contam uma vez
+        let claimer = fixture.claimer;
+        batch(&mut fixture, add(vec![other, claimer, other]), &[user_claim]).unwrap();
+        let event = runtime::events::<SecurityEvent>().pop().unwrap();
+        assert_eq!(event.reason, "2 requested, 2 added, 1 user flags synced");
+        assert_eq!(fixture.bank.read::<BlacklistAccount>(&blacklist).blacklisted_users.len(), 2);
+        assert!(fixture.user_claim().is_blacklisted);
+        assert_eq!(fixture.claim(fixture.args(100)).unwrap_err(), program_error(ErrorCode::Unauthorized));
+
+        batch(&mut fixture, remove(vec![claimer, claimer]), &[user_claim]).unwrap();
+        let event = runtime::events::<SecurityEvent>().pop().unwrap();
+        assert_eq!(event.reason, "1 requested, 1 removed, 1 user flags synced");
+        assert_eq!(fixture.bank.read::<BlacklistAccount>(&blacklist).blacklisted_users, vec![other]);
+        fixture.claim(fixture.args(100)).unwrap();
+    }
 
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-}
+    #[test]
+    fn stored_bumps_match_the_canonical_derivation() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let canonical = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID).1;
+        // Claimer novo: a conta de claim nasce no primeiro claim
+        fixture.claimer = Pubkey::new_unique();
+        let claimer = fixture.claimer;
+        fixture.create_ata(&claimer);
+        fixture.claim_signed(fixture.args(100), 0).unwrap();
+        assert_eq!(fixture.user_claim().bump, canonical(&[b"user_claim", claimer.as_ref()]));
+
+        let blacklist = Pubkey::find_program_address(&[b"blacklist"], &crate::ID).0;
+        let accounts = crate::accounts::InitializeBlacklist {
+            admin: fixture.admin,
+            blacklist,
+            config: config_key(),
+            system_program: system_program::ID,
+        };
+        fixture.bank.execute(&accounts, crate::instruction::InitializeBlacklist {}).unwrap();
+        assert_eq!(fixture.bank.read::<BlacklistAccount>(&blacklist).bump, canonical(&[b"blacklist"]));
+
+        fixture.request_action(AdminActionType::ChangeMaxClaim, Pubkey::default(), 500).unwrap();
+        let pending = fixture.bank.read::<PendingAdminAction>(&fixture.pending_action_key());
+        assert_eq!(pending.bump, canonical(&[b"pending_action", fixture.admin.as_ref()]));
+    }
 
-#[derive(Accounts)]
-pub struct ManageBlacklist<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    #[test]
+    fn numeric_limits_change_only_through_the_timelock() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let config = |fixture: &ClaimFixture| fixture.bank.read::<ConfigAccount>(&config_key());
+
+        // 2.400 por dia => 100 por hora
+        fixture.request_action(AdminActionType::ChangeMaxClaim, Pubkey::default(), 2_400).unwrap();
+        assert_eq!(fixture.execute_action().unwrap_err(), program_error(ErrorCode::InvalidInput));
+        assert_eq!(config(&fixture).max_claim_per_user, 1_000_000);
+        fixture.set_now(fixture.now + ADMIN_ACTION_DELAY_SECONDS);
+        fixture.execute_action().unwrap();
+        assert_eq!(config(&fixture).max_claim_per_user, 2_400);
+        assert_eq!(
+            fixture.claim(fixture.args(101)).unwrap_err(),
+            program_error(ErrorCode::InvalidPaymentAmount)
+        );
+        fixture.claim(fixture.args(100)).unwrap();
+
+        // O supply não pode cair abaixo do que já foi mintado
+        fixture.request_action(AdminActionType::ChangeSupplyLimit, Pubkey::default(), 99).unwrap();
+        fixture.set_now(fixture.now + ADMIN_ACTION_DELAY_SECONDS);
+        assert_eq!(fixture.execute_action().unwrap_err(), program_error(ErrorCode::InvalidInput));
+        let accounts = crate::accounts::CancelAdminAction {
+            admin: fixture.admin,
+            pending_action: fixture.pending_action_key(),
+            config: config_key(),
+        };
+        fixture.bank.execute(&accounts, crate::instruction::CancelAdminAction {}).unwrap();
+
+        fixture.request_action(AdminActionType::ChangeSupplyLimit, Pubkey::default(), 150).unwrap();
+        fixture.set_now(fixture.now + ADMIN_ACTION_DELAY_SECONDS);
+        fixture.execute_action().unwrap();
+        assert_eq!(config(&fixture).total_supply_limit, 150);
+        assert_eq!(
+            fixture.claim(fixture.args(51)).unwrap_err(),
+            program_error(ErrorCode::InvalidPaymentAmount)
+        );
+        fixture.claim(fixture.args(50)).unwrap();
+        assert_eq!(config(&fixture).total_minted, 150);
+    }
 
-    #[account(
-        mut,
-        seeds = [b"blacklist"],
-        bump,
-    )]
-    pub blacklist: Account<'info, BlacklistAccount>,
+    #[test]
+    fn set_mint_authority_rotates_and_guards_the_disable() {
+        use anchor_spl::token_interface::spl_token_2022::{extension::StateWithExtensions, state::Mint};
+
+        let set_authority = |fixture: &mut ClaimFixture, allow_disable| {
+            let accounts = crate::accounts::SetMintAuthority {
+                admin: fixture.admin,
+                pending_action: fixture.pending_action_key(),
+                token_mint: fixture.mint,
+                mint_authority: Pubkey::find_program_address(&[b"mint_authority"], &crate::ID).0,
+                config: config_key(),
+                token_program: fixture.token_program,
+            };
+            fixture.bank.execute(&accounts, crate::instruction::SetMintAuthority { allow_disable })
+        };
+        let mint_authority = |fixture: &ClaimFixture| {
+            let data = &fixture.bank.get(&fixture.mint).data;
+            StateWithExtensions::<Mint>::unpack(data).unwrap().base.mint_authority
+        };
+
+        // Rotação para o programa novo, só depois do delay
+        let mut fixture = ClaimFixture::new(claim_config());
+        let new_authority = Pubkey::new_unique();
+        fixture.request_action(AdminActionType::ChangeMintAuthority, new_authority, 0).unwrap();
+        assert_eq!(set_authority(&mut fixture, false).unwrap_err(), program_error(ErrorCode::InvalidInput));
+        fixture.set_now(fixture.now + ADMIN_ACTION_DELAY_SECONDS);
+        set_authority(&mut fixture, false).unwrap();
+        assert_eq!(mint_authority(&fixture), COption::Some(new_authority));
+        assert_eq!(fixture.bank.read::<ConfigAccount>(&config_key()).mint_authority, new_authority);
+        // A ação pendente é fechada na execução
+        assert_eq!(fixture.bank.get(&fixture.pending_action_key()).owner, system_program::ID);
+
+        // A chave padrão desativa o mint para sempre: só com allow_disable
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.request_action(AdminActionType::ChangeMintAuthority, Pubkey::default(), 0).unwrap();
+        fixture.set_now(fixture.now + ADMIN_ACTION_DELAY_SECONDS);
+        assert_eq!(
+            set_authority(&mut fixture, false).unwrap_err(),
+            program_error(ErrorCode::MintDisableNotConfirmed)
+        );
+        let pda = Pubkey::find_program_address(&[b"mint_authority"], &crate::ID).0;
+        assert_eq!(mint_authority(&fixture), COption::Some(pda));
+        set_authority(&mut fixture, true).unwrap();
+        assert_eq!(mint_authority(&fixture), COption::None);
+        assert_eq!(fixture.bank.read::<ConfigAccount>(&config_key()).mint_authority, Pubkey::default());
+    }
 
-    #[account(
-        mut,
-        seeds = [b"user_claim", user.key().as_ref()],
-        bump,
-    )]
-    pub user_claim_account: Account<'info, UserClaimAccount>,
+    #[test]
+    fn one_signed_root_authorizes_each_claim_of_the_batch() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let args = fixture.args(100);
+        let leaf = |claimer: &Pubkey, args: &ClaimArgs| {
+            keccak::hashv(&[claim_message(claimer, &fixture.mint, args, 0).as_bytes()]).0
+        };
+        // Três folhas: a do claimer e as de outros dois usuários do lote
+        let leaves = [
+            leaf(&fixture.claimer, &args),
+            leaf(&Pubkey::new_unique(), &fixture.args(200)),
+            leaf(&Pubkey::new_unique(), &fixture.args(300)),
+        ];
+        let root = merkle_parent(merkle_parent(leaves[0], leaves[1]), leaves[2]);
+        let root_message = format!(
+            "{{\"batch_root\":\"{}\",\"action\":\"claim_batch\"}}",
+            root.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+        );
+        let accounts = fixture.claim_accounts();
+        let claim = |fixture: &mut ClaimFixture, args: ClaimArgs, proof: Vec<[u8; 32]>| {
+            fixture.sign(&root_message, &args.signature);
+            let batch = ClaimBatchProof { root, proof };
+            fixture.bank.execute(&accounts, crate::instruction::ClaimTokensBatched { args, batch })
+        };
+
+        // Outro valor não é uma folha da raiz assinada
+        let other_amount = fixture.args(101);
+        assert_eq!(
+            claim(&mut fixture, other_amount, vec![leaves[1], leaves[2]]).unwrap_err(),
+            program_error(ErrorCode::InvalidMerkleProof)
+        );
+        claim(&mut fixture, args.clone(), vec![leaves[1], leaves[2]]).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 100);
+        // O nonce avançou: a mesma folha não vale de novo
+        assert_eq!(
+            claim(&mut fixture, args, vec![leaves[1], leaves[2]]).unwrap_err(),
+            program_error(ErrorCode::InvalidMerkleProof)
+        );
+    }
 
-    /// CHECK: Usuário a ser adicionado/removido da blacklist
-    pub user: UncheckedAccount<'info>,
+    #[test]
+    fn mints_near_the_supply_ceiling_fail_before_the_cpi() {
+        use anchor_spl::token_interface::spl_token_2022::state::Mint;
 
-    pub config: Account<'info, ConfigAccount>,
-}
+        let mut fixture = ClaimFixture::new(claim_config());
+        let mint = Mint {
+            mint_authority: COption::Some(Pubkey::find_program_address(&[b"mint_authority"], &crate::ID).0),
+            supply: u64::MAX - 100,
+            decimals: 9,
+            is_initialized: true,
+            ..Mint::default()
+        };
+        fixture.bank.set(fixture.mint, runtime::TestAccount::packed(fixture.token_program, mint));
 
-#[derive(Accounts)]
-pub struct RequestAdminAction<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+        assert_eq!(
 
-    #[account(
-        init,
-        payer = admin,
-        space = 8 + 1 + 32 + 8 + 1, // discriminator + action_type + new_value + requested_at + executed
-        seeds = [b"pending_action", admin.key().as_ref()],
-        bump,
-    )]
-    pub pending_action: Account<'info, PendingAdminAction>,
+            fixture.claim(fixture.args(101)).unwrap_err(),
 
-    pub config: Account<'info, ConfigAccount>,
-    pub system_program: Program<'info, System>,
-}
+            program_error(ErrorCode::MintSupplyOverflow)
 
-#[derive(Accounts)]
-pub struct ExecuteAdminAction<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+        );
+        fixture.claim(fixture.args(100)).unwrap();
 
-    #[account(
-        mut,
-        seeds = [b"pending_action", admin.key().as_ref()],
-        bump,
-        constraint = !pending_action.executed @ ErrorCode::InvalidInput,
-    )]
-    pub pending_action: Account<'info, PendingAdminAction>,
+        let recipient = fixture.claimer;
+        let accounts = fixture.mint_accounts(recipient);
+        assert_eq!(
+            fixture.bank.execute(&accounts, crate::instruction::MintTokens { amount: 1, recipient }).unwrap_err(),
+            program_error(ErrorCode::MintSupplyOverflow)
+        );
+        assert_eq!(fixture.balance(&recipient), 100);
+    }
 
-    #[account(mut)]
-    pub config: Account<'info, ConfigAccount>,
-}
+    #[test]
+    fn weekly_cap_binds_across_days_before_the_daily_cap() {
+        // 2.400 por dia (100 por hora) e 500 por semana
+        let config = ConfigAccount { max_claim_per_user: 2_400, max_claim_per_week: 500, ..claim_config() };
+        let mut fixture = ClaimFixture::new(config);
+        let start = fixture.now;
+        for day in 0..5 {
+            fixture.set_now(start + day * ONE_DAY_SECONDS);
+            fixture.claim(fixture.args(100)).unwrap();
+        }
 
-#[derive(Accounts)]
-pub struct EmergencyPause<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+        // Sexto dia: o diário e o horário estão livres, o semanal não
+        fixture.set_now(start + 5 * ONE_DAY_SECONDS);
+        assert_eq!(
+            fixture.claim(fixture.args(1)).unwrap_err(),
+            program_error(ErrorCode::WeeklyLimitExceeded)
+        );
+        assert_eq!(fixture.user_claim().weekly_claimed, 500);
 
-    #[account(mut)]
-    pub config: Account<'info, ConfigAccount>,
-}
+        fixture.set_now(start + ONE_WEEK_SECONDS);
+        fixture.claim(fixture.args(100)).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 600);
+    }
 
-#[derive(Accounts)]
-pub struct InitializeBlacklist<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    #[test]
+    fn pending_actions_report_ready_at_and_when_they_become_executable() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let requested_at = fixture.now;
+        fixture.request_action(AdminActionType::ChangeMaxClaim, Pubkey::default(), 2_400).unwrap();
+        let pending = fixture.bank.read::<PendingAdminAction>(&fixture.pending_action_key());
+        assert_eq!(pending.requested_at, requested_at);
+        assert_eq!(pending.ready_at, requested_at + ADMIN_ACTION_DELAY_SECONDS);
+        let event = runtime::events::<AdminActionEvent>().pop().unwrap();
+        assert!(event.details.ends_with(&format!("ready_at {}", pending.ready_at)));
+
+        let accounts = crate::accounts::CheckAdminAction {
+            admin: fixture.admin,
+            pending_action: fixture.pending_action_key(),
+            config: config_key(),
+        };
+        let executable_at = |fixture: &mut ClaimFixture, now: i64| {
+            fixture.set_now(now);
+            fixture.bank.execute(&accounts, crate::instruction::CheckAdminAction {}).unwrap();
+            let event = runtime::events::<AdminActionStatusEvent>().pop().unwrap();
+            assert_eq!((event.action.as_str(), event.ready_at), ("ChangeMaxClaim", pending.ready_at));
+            event.executable
+        };
+        assert!(!executable_at(&mut fixture, pending.ready_at - 1));
+        assert!(executable_at(&mut fixture, pending.ready_at));
+    }
 
-    #[account(
-        init,
-        payer = admin,
-        space = 8 + 32 + 4 + (32 * 100), // discriminator + admin + vec length + até 100 usuários
-        seeds = [b"blacklist"],
-        bump,
-    )]
-    pub blacklist: Account<'info, BlacklistAccount>,
+    #[test]
+    fn signatures_are_bound_to_the_mint_passed() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let other_mint = Pubkey::new_unique();
+
+        // Backend assinou o claim para outro mint
+        let args = fixture.args(100);
+        fixture.sign(&claim_message(&fixture.claimer, &other_mint, &args, 0), &args.signature);
+        let accounts = fixture.claim_accounts();
+        assert_eq!(
+            fixture.bank.execute(&accounts, crate::instruction::ClaimTokens { args: args.clone() }).unwrap_err(),
+            program_error(ErrorCode::InvalidSignature)
+        );
+        fixture.claim(args).unwrap();
+
+        // O mesmo para uma queima
+        let ix = fixture.burn_ix(40);
+        let accounts = fixture.burn_accounts();
+        let message = burn_message(
+            &fixture.claimer,
+            &other_mint,
+            ix.amount,
+            ix.timestamp,
+            0,
+            ix.burn_reason_code,
+            ix.reference_id,
+        );
+        fixture.sign(&message, &ix.signature);
+        assert_eq!(
+            fixture.bank.execute(&accounts, ix).unwrap_err(),
+            program_error(ErrorCode::InvalidSignature)
+        );
+        fixture.burn(fixture.burn_ix(40)).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 60);
+    }
 
-    pub config: Account<'info, ConfigAccount>,
-    pub system_program: Program<'info, System>,
-}
+    #[test]
+    fn initialize_config_checks_the_mint_authority() {
+        use anchor_spl::token_interface::spl_token_2022::state::Mint;
+
+        let initialize = |mint_authority: Pubkey, declared: Pubkey| {
+            runtime::start(1_000);
+            let mut bank = runtime::Bank::new();
+            let (admin, token_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+            let mint = Mint {
+                mint_authority: COption::Some(mint_authority),
+                decimals: 9,
+                is_initialized: true,
+                ..Mint::default()
+            };
+            bank.set(token_mint, runtime::TestAccount::packed(spl_token_2022::ID, mint));
+            let accounts = crate::accounts::InitializeConfig {
+                admin,
+                token_mint,
+                config: config_key(),
+                mint_authority: Pubkey::find_program_address(&[b"mint_authority"], &crate::ID).0,
+                system_program: system_program::ID,
+            };
+            let ix = crate::instruction::InitializeConfig {
+                payment_token_mint: token_mint,
+                max_claim_per_user: 1_000,
+                total_supply_limit: 21_000_000 * 1_000_000_000,
+                backend_authority: Pubkey::new_unique(),
+                guardian: Pubkey::new_unique(),
+                mint_authority: declared,
+            };
+            bank.execute(&accounts, ix)
+        };
+        let pda = Pubkey::find_program_address(&[b"mint_authority"], &crate::ID).0;
+
+        // A authority declarada precisa ser o PDA do programa
+        assert_eq!(
+            initialize(pda, Pubkey::new_unique()).unwrap_err(),
+            program_error(ErrorCode::MintAuthorityMismatch)
+        );
+        initialize(pda, pda).unwrap();
+        assert!(runtime::events::<SecurityEvent>().is_empty());
+
+        // Mint ainda com a authority do deploy: a config é criada e o aviso emitido
+        initialize(Pubkey::new_unique(), pda).unwrap();
+        let events = runtime::events::<SecurityEvent>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "MINT_AUTHORITY_MISMATCH");
+    }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("The signature is invalid.")]
-    InvalidSignature,
+    #[test]
+    fn banned_users_can_appeal_but_stay_banned() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let (blacklist, bump) = Pubkey::find_program_address(&[b"blacklist"], &crate::ID);
+        let list = BlacklistAccount { admin: fixture.admin, blacklisted_users: Vec::new(), bump };
+        fixture.bank.set(blacklist, runtime::TestAccount::anchor(&list));
+        let accounts = crate::accounts::RequestUnban {
+            user: fixture.claimer,
+            blacklist,
+            user_claim_account: fixture.user_claim_key(),
+            blacklist_entry: None,
+            config: config_key(),
+        };
+
+        // Quem não está banido não tem o que contestar
+        assert_eq!(
+            fixture.bank.execute(&accounts, crate::instruction::RequestUnban {}).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
 
-    #[msg("The signature is expired.")]
-    ExpiredSignature,
+        let list = BlacklistAccount { blacklisted_users: vec![fixture.claimer], ..list };
+        fixture.bank.set(blacklist, runtime::TestAccount::anchor(&list));
+        fixture.bank.execute(&accounts, crate::instruction::RequestUnban {}).unwrap();
+        let user_claim = fixture.user_claim();
+        assert!(user_claim.appeal_requested);
+        assert_eq!(user_claim.appeal_timestamp, fixture.now);
+        let event = runtime::events::<SecurityEvent>().pop().unwrap();
+        assert_eq!((event.event_type.as_str(), event.user), ("UNBAN_REQUESTED", fixture.claimer));
+
+        // Um pedido por vez, e o ban continua valendo
+        assert_eq!(
+            fixture.bank.execute(&accounts, crate::instruction::RequestUnban {}).unwrap_err(),
+            program_error(ErrorCode::InvalidInput)
+        );
+        assert_eq!(fixture.claim(fixture.args(100)).unwrap_err(), program_error(ErrorCode::Unauthorized));
+        assert_eq!(fixture.balance(&fixture.claimer), 0);
+    }
 
-    #[msg("Você não está autorizado a realizar esta ação")]
-    Unauthorized,
+    #[test]
+    fn claims_commit_to_the_signed_game_session_hash() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        // O claim de uma sessão cria o ClaimedSession [b"claimed_session", hash]
+        let with_session = |hash: [u8; 32]| crate::accounts::ClaimTokens {
+            claimed_session: Some(Pubkey::find_program_address(&[b"claimed_session", hash.as_ref()], &crate::ID).0),
+            ..fixture.claim_accounts()
+        };
+        let (tampered_accounts, signed_accounts) = (with_session([8u8; 32]), with_session([7u8; 32]));
+        let signed = ClaimArgs { game_session_hash: [7u8; 32], ..fixture.args(100) };
+
+        // O backend assinou a sessão 7; o claim revela outra
+        fixture.sign(&claim_message(&fixture.claimer, &fixture.mint, &signed, 0), &signed.signature);
+        let tampered = ClaimArgs { game_session_hash: [8u8; 32], ..signed.clone() };
+        assert_eq!(
+            fixture.bank.execute(&tampered_accounts, crate::instruction::ClaimTokens { args: tampered }).unwrap_err(),
+            program_error(ErrorCode::InvalidSignature)
+        );
 
-    #[msg("Token de pagamento inválido")]
-    InvalidPaymentToken,
+        fixture.claim_with(&signed_accounts, signed, 0).unwrap();
+        let event = runtime::events::<TokenClaimEvent>().pop().unwrap();
+        assert_eq!(event.game_session_hash, [7u8; 32]);
+        assert_eq!(fixture.balance(&fixture.claimer), 100);
+    }
 
-    #[msg("Valor de pagamento inválido")]
-    InvalidPaymentAmount,
+    #[test]
+    fn burn_events_carry_the_signed_reason_code() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(100)).unwrap();
+        let ix = |fixture: &ClaimFixture| crate::instruction::BurnTokens {
+            burn_reason_code: 3,
+            reference_id: 42,
+            description: String::new(),
+            ..fixture.burn_ix(40)
+        };
+
+        // O código do motivo faz parte da mensagem assinada
+        let signed = ix(&fixture);
+        let accounts = fixture.burn_accounts();
+        let message = burn_message(&fixture.claimer, &fixture.mint, 40, signed.timestamp, 0, 3, 42);
+        fixture.sign(&message, &signed.signature);
+        let tampered = crate::instruction::BurnTokens { burn_reason_code: 4, ..signed };
+        assert_eq!(
+            fixture.bank.execute(&accounts, tampered).unwrap_err(),
+            program_error(ErrorCode::InvalidSignature)
+        );
 
-    #[msg("Token de pagamento não configurado")]
-    PaymentTokenNotConfigured,
+        fixture.burn(ix(&fixture)).unwrap();
+        let event = runtime::events::<TokenBurnEvent>().pop().unwrap();
+        assert_eq!((event.burn_reason_code, event.reference_id), (3, 42));
+        assert_eq!(event.description, "");
+        assert_eq!(fixture.balance(&fixture.claimer), 60);
+    }
 
-    #[msg("Fundos insuficientes")]
-    InsufficientFunds,
+    #[test]
+    fn claim_fee_at_zero_and_at_the_maximum() {
+        // Sem taxa: nada vai ao tesouro e só o claim conta no supply
+        let mut fixture = ClaimFixture::new(claim_config());
+        fixture.claim(fixture.args(10_000)).unwrap();
+        assert_eq!(runtime::events::<TokenClaimEvent>()[0].fee_amount, 0);
+        assert_eq!(fixture.bank.read::<ConfigAccount>(&config_key()).total_minted, 10_000);
+
+        // Taxa máxima (10%), também contada no supply
+        let (treasury, treasury_bump) = Pubkey::find_program_address(&[b"treasury"], &crate::ID);
+        let config = ConfigAccount { total_supply_limit: 21_000, treasury_bump, ..claim_config() };
+        let mut fixture = ClaimFixture::new(config);
+        let update = crate::accounts::UpdateConfig { admin: fixture.admin, config: config_key() };
+        let mut set_fee = |claim_fee_bps| {
+            fixture.bank.execute(&update, crate::instruction::SetClaimFeeBps { claim_fee_bps })
+        };
+        assert_eq!(set_fee(MAX_CLAIM_FEE_BPS + 1).unwrap_err(), program_error(ErrorCode::InvalidInput));
+        set_fee(MAX_CLAIM_FEE_BPS).unwrap();
+        let accounts = crate::accounts::ClaimTokens {
+            treasury_token_account: Some(fixture.create_ata(&treasury)),
+            ..fixture.claim_accounts()
+        };
+        fixture.claim_with(&accounts, fixture.args(10_000), 0).unwrap();
+        assert_eq!(fixture.balance(&fixture.claimer), 10_000);
+        assert_eq!(fixture.balance(&treasury), 1_000);
+        assert_eq!(runtime::events::<TokenClaimEvent>()[0].fee_amount, 1_000);
+        assert_eq!(fixture.bank.read::<ConfigAccount>(&config_key()).total_minted, 11_000);
+
+        // 10.000 + 1.000 de taxa passariam do limite de 21.000
+        assert_eq!(
+            fixture.claim_with(&accounts, fixture.args(10_000), 1).unwrap_err(),
+            program_error(ErrorCode::InvalidPaymentAmount)
+        );
+        fixture.claim_with(&accounts, fixture.args(9_000), 1).unwrap();
+        assert_eq!(fixture.balance(&treasury), 1_900);
+    }
 
-    #[msg("O sistema está pausado para emergência")]
-    SystemPaused,
+    #[test]
+    fn absurd_amounts_are_rejected_up_front() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        for amount in [MAX_REASONABLE_AMOUNT + 1, u64::MAX] {
+            assert_eq!(
+                fixture.claim(fixture.args(amount)).unwrap_err(),
+                program_error(ErrorCode::InvalidPaymentAmount)
+            );
+            assert_eq!(
+                fixture.burn(fixture.burn_ix(amount)).unwrap_err(),
+                program_error(ErrorCode::InvalidPaymentAmount)
+            );
+            let recipient = fixture.claimer;
+            let accounts = fixture.mint_accounts(recipient);
+            assert_eq!(
+                fixture.bank.execute(&accounts, crate::instruction::MintTokens { amount, recipient }).unwrap_err(),
+                program_error(ErrorCode::InvalidPaymentAmount)
+            );
+        }
+        // Rejeitados antes da verificação da assinatura (nenhum SIG_REJECT)
+        assert!(runtime::events::<SecurityEvent>().is_empty());
+    }
 
-    #[msg("Valor de entrada inválido")]
-    InvalidInput,
+    #[test]
+    fn get_config_returns_the_stored_values() {
+        use anchor_lang::solana_program::program::get_return_data;
+
+        let mut fixture = ClaimFixture::new(claim_config());
+        let config = ConfigAccount {
+            decimals: 6,
+            max_claim_per_week: 5_000,
+            min_claim_amount: 10,
+            total_minted: 1_234,
+            claim_burn_bps: 50,
+            claim_fee_bps: 100,
+            allowlist_enabled: true,
+            emergency_paused: true,
+            pause_expires_at: fixture.now + 60,
+            ..fixture.bank.read(&config_key())
+        };
+        fixture.bank.set(config_key(), config_account(fixture.admin, config.clone()));
+        let read_back = |fixture: &mut ClaimFixture| {
+            let accounts = crate::accounts::GetConfig { config: config_key() };
+            fixture.bank.execute(&accounts, crate::instruction::GetConfig {}).unwrap();
+            let (program_id, data) = get_return_data().unwrap();
+            assert_eq!(program_id, crate::ID);
+            ConfigView::try_from_slice(&data).unwrap()
+        };
+
+        let view = read_back(&mut fixture);
+        assert_eq!(view.version, CONFIG_VIEW_VERSION);
+        assert_eq!((view.admin, view.payment_token_mint, view.decimals), (fixture.admin, fixture.mint, 6));
+        assert_eq!(
+            (view.max_claim_per_user, view.max_claim_per_week, view.min_claim_amount, view.max_claim_amount),
+            (config.max_claim_per_user, 5_000, 10, config.max_claim_amount)
+        );
+        assert_eq!((view.total_supply_limit, view.total_minted), (config.total_supply_limit, 1_234));
+        assert_eq!((view.claim_burn_bps, view.claim_fee_bps, view.allowlist_enabled), (50, 100, true));
+        assert!(view.emergency_paused);
 
-    #[msg("Erro de overflow matemático")]
-    MathOverflow,
+        // A pausa com prazo vencido aparece como encerrada
+        fixture.set_now(config.pause_expires_at + 1);
+        assert!(!read_back(&mut fixture).emergency_paused);
+    }
+
+    #[test]
+    fn claims_work_with_and_without_an_initialized_blacklist() {
+        // Sem blacklist: ninguém está na lista, mas a flag do usuário vale
+        let mut fixture = ClaimFixture::new(claim_config());
+        let (blacklist, bump) = Pubkey::find_program_address(&[b"blacklist"], &crate::ID);
+        assert!(!fixture.bank.contains(&blacklist));
+        fixture.claim(fixture.args(100)).unwrap();
+        let user_claim = UserClaimAccount { is_blacklisted: true, ..fixture.user_claim() };
+        fixture.bank.set(fixture.user_claim_key(), runtime::TestAccount::anchor(&user_claim));
+        assert_eq!(fixture.claim(fixture.args(100)).unwrap_err(), program_error(ErrorCode::Unauthorized));
+
+        // Com blacklist: a lista bloqueia quem está nela, e só essas carteiras
+        let mut fixture = ClaimFixture::new(claim_config());
+        let other = Pubkey::new_unique();
+        let list = BlacklistAccount { admin: fixture.admin, blacklisted_users: vec![other], bump };
+        fixture.bank.set(blacklist, runtime::TestAccount::anchor(&list));
+        fixture.claim(fixture.args(100)).unwrap();
+        let list = BlacklistAccount { blacklisted_users: vec![fixture.claimer], ..list };
+        fixture.bank.set(blacklist, runtime::TestAccount::anchor(&list));
+        assert_eq!(fixture.claim(fixture.args(100)).unwrap_err(), program_error(ErrorCode::Unauthorized));
+        assert_eq!(fixture.balance(&fixture.claimer), 100);
+    }
+
+    #[test]
+    fn user_claim_bump_must_be_the_canonical_one() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let claimer = fixture.claimer;
+        let (user_claim, canonical) = Pubkey::find_program_address(&[b"user_claim", claimer.as_ref()], &crate::ID);
+        assert_eq!(fixture.user_claim().bump, canonical);
+        // Maior bump abaixo do canônico que também gera um endereço fora da curva
+        let address = |bump: u8| {
+            Pubkey::create_program_address(&[b"user_claim", claimer.as_ref(), &[bump]], &crate::ID)
+        };
+        let other_bump = (0..canonical).rev().find(|bump| address(*bump).is_ok()).unwrap();
+        let other_address = address(other_bump).unwrap();
+        let seeds_error = ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintSeeds as u32);
+
+        // Conta no endereço canônico com um bump não canônico gravado
+        let (blacklist, bump) = Pubkey::find_program_address(&[b"blacklist"], &crate::ID);
+        let list = BlacklistAccount { admin: fixture.admin, blacklisted_users: Vec::new(), bump };
+        fixture.bank.set(blacklist, runtime::TestAccount::anchor(&list));
+        let tampered = UserClaimAccount { bump: other_bump, ..fixture.user_claim() };
+        fixture.bank.set(user_claim, runtime::TestAccount::anchor(&tampered));
+        let sync = crate::accounts::SyncBlacklistFlag {
+            blacklist,
+            user_claim_account: user_claim,
+            user: claimer,
+            config: config_key(),
+        };
+        assert_eq!(
+            fixture.bank.execute(&sync, crate::instruction::SyncBlacklistFlag {}).unwrap_err(),
+            seeds_error
+        );
+
+        // Conta no endereço do bump não canônico: o claim deriva o canônico
+        fixture.bank.set(other_address, runtime::TestAccount::anchor(&tampered));
+        let accounts = crate::accounts::ClaimTokens {
+            user_claim_account: other_address,
+            user_status: other_address,
+            ..fixture.claim_accounts()
+        };
+        assert_eq!(fixture.claim_with(&accounts, fixture.args(100), 0).unwrap_err(), seeds_error);
+
+        let restored = UserClaimAccount { bump: canonical, ..tampered };
+        fixture.bank.set(user_claim, runtime::TestAccount::anchor(&restored));
+        fixture.bank.execute(&sync, crate::instruction::SyncBlacklistFlag {}).unwrap();
+        fixture.claim(fixture.args(100)).unwrap();
+    }
+
+    #[test]
+    fn each_admin_holds_one_pending_action_until_it_is_closed() {
+        let mut fixture = ClaimFixture::new(claim_config());
+        let pending_action = fixture.pending_action_key();
+        let cancel = crate::accounts::CancelAdminAction {
+            admin: fixture.admin,
+            pending_action,
+            config: config_key(),
+        };
+
+        // O único slot do admin está ocupado
+        fixture.request_action(AdminActionType::ChangeMaxClaim, Pubkey::default(), 2_400).unwrap();
+        assert!(fixture.request_action(AdminActionType::ChangeSupplyLimit, Pubkey::default(), 500).is_err());
+        let pending = fixture.bank.read::<PendingAdminAction>(&pending_action);
+        assert_eq!((pending.action_type, pending.new_amount), (AdminActionType::ChangeMaxClaim, 2_400));
+
+        // Cancelar devolve o rent e libera o slot
+        let admin_before = fixture.bank.get(&fixture.admin).lamports;
+        let rent = fixture.bank.get(&pending_action).lamports;
+        fixture.bank.execute(&cancel, crate::instruction::CancelAdminAction {}).unwrap();
+        assert_eq!(fixture.bank.get(&fixture.admin).lamports, admin_before + rent);
+        fixture.request_action(AdminActionType::ChangeSupplyLimit, Pubkey::default(), 500).unwrap();
+
+        // Executar também fecha a ação
+        fixture.set_now(fixture.now + ADMIN_ACTION_DELAY_SECONDS);
+        fixture.execute_action().unwrap();
+        assert_eq!(fixture.bank.get(&pending_action).owner, system_program::ID);
+        fixture.request_action(AdminActionType::ChangeMaxClaim, Pubkey::default(), 2_400).unwrap();
+    }
 }